@@ -0,0 +1,68 @@
+//! Derive macros for Axiom.
+//!
+//! These macros are re-exported by Axiom when the `derive` feature is enabled so that users
+//! don't need to depend on this crate directly.
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::punctuated::Punctuated;
+use syn::{parse_macro_input, DeriveInput, Token, Type};
+
+/// Derives `axiom::actors::Dispatch` for an actor type that implements `Handler<M>` for each
+/// of the message types listed in the `#[handles(...)]` attribute. The generated dispatch
+/// tries each of the types in the order listed and passes messages that none of them accept to
+/// `Dispatch::unhandled`.
+///
+/// # Examples
+/// ```ignore
+/// #[derive(Dispatch)]
+/// #[handles(Deposit, Withdraw)]
+/// struct Account {
+///     balance: u64,
+/// }
+/// ```
+#[proc_macro_derive(Dispatch, attributes(handles))]
+pub fn derive_dispatch(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    // Collect the message types from all of the `handles` attributes.
+    let mut types: Vec<Type> = Vec::new();
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("handles")) {
+        match attr.parse_args_with(Punctuated::<Type, Token![,]>::parse_terminated) {
+            Ok(parsed) => types.extend(parsed),
+            Err(error) => return error.to_compile_error().into(),
+        }
+    }
+    if types.is_empty() {
+        let message = "expected a `#[handles(...)]` attribute listing the handled message types";
+        return syn::Error::new_spanned(&input.ident, message)
+            .to_compile_error()
+            .into();
+    }
+
+    let name = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    let expanded = quote! {
+        impl #impl_generics ::axiom::actors::Dispatch for #name #type_generics #where_clause {
+            fn dispatch(
+                self,
+                context: ::axiom::actors::Context,
+                message: ::axiom::message::Message,
+            ) -> impl ::std::future::Future<Output = ::axiom::ActorResult<Self>>
+                   + ::std::marker::Send {
+                async move {
+                    #(
+                        if let ::std::option::Option::Some(msg) = message.content_as::<#types>() {
+                            return ::axiom::actors::Handler::<#types>::handle(self, context, msg)
+                                .await;
+                        }
+                    )*
+                    ::axiom::actors::Dispatch::unhandled(self, context, message)
+                }
+            }
+        }
+    };
+    expanded.into()
+}
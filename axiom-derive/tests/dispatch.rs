@@ -0,0 +1,117 @@
+//! Tests for `#[derive(Dispatch)]`, which are here rather than in Axiom itself because the
+//! generated code refers to Axiom by its crate name.
+
+use axiom::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::marker::PhantomData;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Deposits the given amount to the account.
+#[derive(Serialize, Deserialize)]
+struct Deposit(u64);
+
+/// Withdraws the given amount from the account if the balance allows it.
+#[derive(Serialize, Deserialize)]
+struct Withdraw(u64);
+
+/// Asks the account for its balance.
+#[derive(Serialize, Deserialize)]
+struct Balance;
+
+#[derive(Dispatch)]
+#[handles(Deposit, Withdraw)]
+#[handles(Balance)]
+struct Account {
+    balance: u64,
+}
+
+impl Handler<Deposit> for Account {
+    async fn handle(mut self, _: Context, message: Arc<Deposit>) -> ActorResult<Self> {
+        self.balance += message.0;
+        Ok(Status::done(self))
+    }
+}
+
+impl Handler<Withdraw> for Account {
+    async fn handle(mut self, _: Context, message: Arc<Withdraw>) -> ActorResult<Self> {
+        if message.0 <= self.balance {
+            self.balance -= message.0;
+        }
+        Ok(Status::done(self))
+    }
+}
+
+impl Handler<Balance> for Account {
+    async fn handle(self, context: Context, _: Arc<Balance>) -> ActorResult<Self> {
+        if let Some(reply_to) = context.reply_to() {
+            reply_to.send_new(self.balance)?;
+        }
+        Ok(Status::done(self))
+    }
+}
+
+/// An actor that replies with the message it was sent, which has a generic parameter to make
+/// sure that the generics are carried to the derived impl.
+#[derive(Dispatch)]
+#[handles(T)]
+struct Echo<T: 'static + Send + Sync + Serialize + for<'de> Deserialize<'de>> {
+    marker: PhantomData<T>,
+}
+
+impl<T> Handler<T> for Echo<T>
+where
+    T: 'static + Send + Sync + Serialize + for<'de> Deserialize<'de>,
+{
+    async fn handle(self, context: Context, message: Arc<T>) -> ActorResult<Self> {
+        if let Some(reply_to) = context.reply_to() {
+            reply_to.send_arc(message)?;
+        }
+        Ok(Status::done(self))
+    }
+}
+
+fn init_test_log() {
+    let _ = env_logger::builder().is_test(true).try_init();
+}
+
+/// Tests that each message is routed to the handler for its type and that messages no handler
+/// accepts are passed to the fallback without stopping the actor.
+#[test]
+fn test_derive_dispatch() {
+    init_test_log();
+    let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+    let aid = system
+        .spawn()
+        .with(Account { balance: 0 }, Account::dispatch)
+        .unwrap();
+    let timeout = Duration::from_millis(1000);
+
+    aid.send_new(Deposit(100)).unwrap();
+    aid.send_new(Withdraw(30)).unwrap();
+    aid.send_new(Withdraw(300)).unwrap();
+    aid.send_new("unhandled".to_string()).unwrap();
+    let balance = aid.ask_blocking::<u64>(Message::new(Balance), timeout);
+    assert_eq!(70, *balance.unwrap());
+    assert!(system.is_actor_alive(&aid));
+    assert_eq!(1, system.dead_letter_count(DeadLetterReason::Unhandled));
+
+    system.trigger_and_await_shutdown(None);
+}
+
+/// Tests that actors with generic parameters can be derived.
+#[test]
+fn test_derive_dispatch_generics() {
+    init_test_log();
+    let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+    let echo = Echo::<i32> {
+        marker: PhantomData,
+    };
+    let aid = system.spawn().with(echo, Echo::dispatch).unwrap();
+    let timeout = Duration::from_millis(1000);
+
+    let reply = aid.ask_blocking::<i32>(Message::new(11), timeout);
+    assert_eq!(11, *reply.unwrap());
+
+    system.trigger_and_await_shutdown(None);
+}
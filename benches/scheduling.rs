@@ -0,0 +1,109 @@
+//! Benchmarks the cost of scheduling and cancelling delayed messages with a large number of
+//! messages already pending, and of scheduling from several threads at once with a different
+//! number of timer shards.
+
+use axiom::prelude::*;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// The delay for all of the scheduled messages, long enough that none are sent during the
+/// benchmarks.
+const DELAY: Duration = Duration::from_secs(600);
+
+/// Creates an actor system with the given number of timer shards and an actor to schedule
+/// messages to.
+fn setup(shards: u16) -> (ActorSystem, Aid) {
+    let config = ActorSystemConfig::default()
+        .thread_pool_size(2)
+        .timer_shards(shards);
+    let system = ActorSystem::create(config);
+    let aid = system
+        .spawn()
+        .with((), |state: (), _: Context, _: Message| async move {
+            Ok(Status::done(state))
+        })
+        .unwrap();
+    (system, aid)
+}
+
+/// Schedules `count` messages to the `aid`, spread over the next minutes so that they end up
+/// on all of the levels of the timer.
+fn fill(aid: &Aid, count: u64) -> Vec<ScheduleHandle> {
+    (0..count)
+        .map(|i| {
+            let delay = DELAY + Duration::from_millis(i * 7 % 300_000);
+            aid.send_new_after(i, delay).unwrap()
+        })
+        .collect()
+}
+
+fn schedule(c: &mut Criterion) {
+    let mut group = c.benchmark_group("schedule");
+    for pending in [0, 100_000, 500_000].iter() {
+        let (system, aid) = setup(1);
+        let _handles = fill(&aid, *pending);
+        group.bench_with_input(BenchmarkId::from_parameter(pending), pending, |b, _| {
+            // The messages are cancelled after each sample to keep them from piling up.
+            b.iter_custom(|iters| {
+                let start = Instant::now();
+                let handles: Vec<ScheduleHandle> = (0..iters)
+                    .map(|_| aid.send_new_after(11, DELAY).unwrap())
+                    .collect();
+                let elapsed = start.elapsed();
+                handles.iter().for_each(ScheduleHandle::cancel);
+                elapsed
+            })
+        });
+        system.trigger_and_await_shutdown(None);
+    }
+    group.finish();
+}
+
+fn cancel(c: &mut Criterion) {
+    let mut group = c.benchmark_group("schedule_and_cancel");
+    for pending in [0, 100_000, 500_000].iter() {
+        let (system, aid) = setup(1);
+        let _handles = fill(&aid, *pending);
+        group.bench_with_input(BenchmarkId::from_parameter(pending), pending, |b, _| {
+            b.iter(|| aid.send_new_after(11, DELAY).unwrap().cancel())
+        });
+        system.trigger_and_await_shutdown(None);
+    }
+    group.finish();
+}
+
+fn contended(c: &mut Criterion) {
+    let threads: u32 = 8;
+    let mut group = c.benchmark_group("schedule_8_threads");
+    for shards in [1, 4, 8].iter() {
+        let (system, aid) = setup(*shards);
+        group.bench_with_input(BenchmarkId::new("shards", shards), shards, |b, _| {
+            b.iter_custom(|iters| {
+                let start = Instant::now();
+                let workers: Vec<_> = (0..threads)
+                    .map(|_| {
+                        let aid = aid.clone();
+                        thread::spawn(move || {
+                            (0..iters)
+                                .map(|_| aid.send_new_after(11, DELAY).unwrap())
+                                .collect::<Vec<_>>()
+                        })
+                    })
+                    .collect();
+                let handles: Vec<ScheduleHandle> = workers
+                    .into_iter()
+                    .flat_map(|worker| worker.join().unwrap())
+                    .collect();
+                let elapsed = start.elapsed() / threads;
+                handles.iter().for_each(ScheduleHandle::cancel);
+                elapsed
+            })
+        });
+        system.trigger_and_await_shutdown(None);
+    }
+    group.finish();
+}
+
+criterion_group!(benches, schedule, cancel, contended);
+criterion_main!(benches);
@@ -6,8 +6,10 @@
 //! created by calling `system::spawn().with()` with any kind of function or closure that
 //! implements the `Processor` trait.
 
-use crate::message::ActorMessage;
+use crate::message::{ActorMessage, MessagePriority};
 use crate::prelude::*;
+use futures::channel::oneshot;
+use futures::future::{AbortHandle, BoxFuture};
 use futures::{FutureExt, Stream};
 use log::{debug, error, trace, warn};
 use secc::*;
@@ -15,17 +17,19 @@ use serde::de::Deserializer;
 use serde::ser::Serializer;
 use serde::{Deserialize, Serialize};
 use std::cell::UnsafeCell;
+use std::collections::VecDeque;
 use std::fmt::Debug;
 use std::future::Future;
 use std::hash::{Hash, Hasher};
-use std::marker::{Send, Sync};
+use std::iter;
+use std::marker::{PhantomData, Send, Sync};
 use std::panic::{catch_unwind, AssertUnwindSafe};
 use std::pin::Pin;
 use std::ptr;
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
-use std::task::Poll;
-use std::time::Duration;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Poll, Waker};
+use std::time::{Duration, Instant};
 use uuid::Uuid;
 
 /// Status of the message and potentially the actor as a resulting from processing a message
@@ -109,6 +113,10 @@ pub enum AidError {
     /// taking too long to clear the messages.
     SendTimedOut(Aid),
 
+    /// Returned when sending to an actor whose mailbox is full, if the actor was spawned with
+    /// [`MailboxOverflow::FailFast`].
+    MailboxFull(Aid),
+
     /// Used when unable to schedule the actor for work in the work channel. This could be a
     /// result of having a work channel that is too small to accommodate the number of actors
     /// being concurrently scheduled, not enough threads to process actors in the channel fast
@@ -125,6 +133,51 @@ impl std::fmt::Display for AidError {
 
 impl std::error::Error for AidError {}
 
+/// Errors returned when asking an actor for a reply with [`Aid::ask`].
+#[derive(Debug, PartialEq, Eq)]
+pub enum AskError {
+    /// The message asking for a reply could not be sent to the actor.
+    SendFailed(AidError),
+
+    /// The actor did not reply within the timeout given when asking.
+    TimedOut,
+
+    /// The actor stopped before it replied. Note that this can only be detected for actors on
+    /// the local actor system; a remote actor that stops will result in a [`AskError::TimedOut`].
+    ActorStopped,
+
+    /// The actor replied with a message that was not of the type that was asked for.
+    UnexpectedReply,
+}
+
+impl std::fmt::Display for AskError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl std::error::Error for AskError {}
+
+/// Errors returned by [`Context::stash`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum StashError {
+    /// The stash of the actor already holds as many messages as it can, as set with
+    /// [`ActorBuilder::stash_capacity`].
+    Full,
+}
+
+impl std::fmt::Display for StashError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl std::error::Error for StashError {}
+
+/// The message sent to a reply endpoint when the time given to the actor to reply has elapsed.
+#[derive(Serialize, Deserialize)]
+struct AskTimedOut;
+
 /// An enum that holds a sender for an actor.
 ///
 /// An [`Aid`] uses the sender to send messages to the destination actor. Messages that are
@@ -135,16 +188,35 @@ enum ActorSender {
         /// Holds a boolean to indicate if the actor is stopped. A stopped actor will no longer
         /// accept further messages to be sent.
         stopped: AtomicBool,
-        /// The send side of the actor's message channel.
-        sender: SeccSender<Message>,
+        /// The send side of the actor's mailbox.
+        mailbox: Arc<Mailbox>,
         /// The reference to the local [`ActorSystem`] that the `aid` is on.
         system: ActorSystem,
+        /// What happens to messages sent while the actor's mailbox is full.
+        overflow: MailboxOverflow,
     },
 
     /// A sender that is used when an actor is on another actor system. Messages are wrapped in a
     /// [`WireMessage`] struct and it will be up to the cluster implementation to get the messages
     /// to the remote system.
-    Remote { sender: SeccSender<WireMessage> },
+    Remote {
+        /// The send side of the channel to the remote actor system.
+        sender: SeccSender<WireMessage>,
+        /// The reference to the local [`ActorSystem`] that the `aid` was deserialized on.
+        system: ActorSystem,
+    },
+
+    /// A sender for the temporary endpoint that receives the reply to an [`Aid::ask`]. This is
+    /// not an actor; the first message sent is handed to the asker and any later messages are
+    /// discarded.
+    Reply {
+        /// The send side of the channel to the asker which is taken by the first message sent.
+        sender: Mutex<Option<oneshot::Sender<Message>>>,
+        /// The actor that was asked, which the endpoint monitors until it gets a message.
+        asked: Aid,
+        /// The reference to the local [`ActorSystem`] that the endpoint is registered on.
+        system: ActorSystem,
+    },
 }
 
 impl std::fmt::Debug for ActorSender {
@@ -155,11 +227,433 @@ impl std::fmt::Debug for ActorSender {
             match *self {
                 ActorSender::Local { .. } => "ActorSender::Local",
                 ActorSender::Remote { .. } => "ActorSender::Remote",
+                ActorSender::Reply { .. } => "ActorSender::Reply",
             }
         )
     }
 }
 
+/// Assigns a priority to the messages sent to an actor with a priority mailbox. See
+/// [`ActorBuilder::classify`].
+pub(crate) type Classifier = Arc<dyn Fn(&Message) -> MessagePriority + Send + Sync>;
+
+/// The send side of the mailbox of a local actor. The mailbox is a single message channel,
+/// unless the actor was spawned with [`ActorBuilder::priority_mailbox`], in which case it has a
+/// lane for system messages and one for each [`MessagePriority`] that the actor takes messages
+/// from in that order.
+struct Mailbox {
+    /// The lanes of the mailbox, in the order that the actor takes messages from them.
+    lanes: Vec<Lane>,
+    /// Assigns a priority to the messages that aren't system messages, instead of the priority
+    /// that they were sent with.
+    classifier: Option<Classifier>,
+}
+
+/// A message channel of a [`Mailbox`] and whatever is waiting for room in it.
+struct Lane {
+    /// The send side of the message channel.
+    sender: SeccSender<Message>,
+    /// The senders waiting for room in the message channel.
+    waiters: SendWaiters,
+    /// The messages that didn't fit in the message channel, if the actor was spawned with
+    /// `MailboxOverflow::DropOldest` or `MailboxOverflow::Unbounded`.
+    buffer: Option<OverflowBuffer>,
+}
+
+impl Lane {
+    /// Creates a lane that holds `channel_size` messages, returning its receive side as well.
+    fn new(channel_size: u16, overflow: MailboxOverflow) -> (Lane, SeccReceiver<Message>) {
+        let (sender, receiver) = secc::create::<Message>(channel_size, Duration::from_millis(10));
+        let buffer = match overflow {
+            MailboxOverflow::DropOldest | MailboxOverflow::Unbounded => {
+                Some(OverflowBuffer::new(sender.clone(), channel_size, overflow))
+            }
+            _ => None,
+        };
+        let lane = Lane {
+            sender,
+            waiters: SendWaiters::default(),
+            buffer,
+        };
+        (lane, receiver)
+    }
+
+    /// The number of messages that the actor took straight out of the overflow buffer of the
+    /// lane, which are never counted by the message channel.
+    fn taken(&self) -> usize {
+        self.buffer
+            .as_ref()
+            .map_or(0, |buffer| buffer.taken.load(Ordering::Relaxed))
+    }
+}
+
+impl Mailbox {
+    /// Creates the mailbox of an actor spawned with the `builder`, with lanes that hold
+    /// `channel_size` messages each, returning the receive sides of the lanes as well.
+    fn new(builder: &ActorBuilder, channel_size: u16) -> (Mailbox, Vec<SeccReceiver<Message>>) {
+        let lanes = if builder.priority_mailbox { 4 } else { 1 };
+        let (lanes, receivers) = (0..lanes)
+            .map(|_| Lane::new(channel_size, builder.overflow))
+            .unzip();
+        let mailbox = Mailbox {
+            lanes,
+            classifier: builder.classifier.clone(),
+        };
+        (mailbox, receivers)
+    }
+
+    /// The lane that the `message` is sent to, which is the lane of system messages for
+    /// [`SystemMsg`]s and otherwise the lane of the priority of the message.
+    fn lane(&self, message: &Message) -> &Lane {
+        if self.lanes.len() == 1 || message.content_as::<SystemMsg>().is_some() {
+            return &self.lanes[0];
+        }
+        let priority = match &self.classifier {
+            Some(classifier) => classifier(message),
+            None => message.priority(),
+        };
+        match priority {
+            MessagePriority::High => &self.lanes[1],
+            MessagePriority::Normal => &self.lanes[2],
+            MessagePriority::Low => &self.lanes[3],
+        }
+    }
+
+    /// The number of messages sent to the mailbox.
+    fn sent(&self) -> usize {
+        self.lanes
+            .iter()
+            .map(|lane| lane.sender.sent() + lane.taken())
+            .sum()
+    }
+
+    /// The number of messages taken out of the mailbox.
+    fn received(&self) -> usize {
+        self.lanes
+            .iter()
+            .map(|lane| lane.sender.received() + lane.taken())
+            .sum()
+    }
+
+    /// The overflow buffer of the lane at the index `lane`, if it has one.
+    fn buffer(&self, lane: usize) -> Option<&OverflowBuffer> {
+        self.lanes[lane].buffer.as_ref()
+    }
+
+    /// Moves the messages that didn't fit in the lanes into them, given the `receivers` of the
+    /// lanes. See [`OverflowBuffer::refill`].
+    fn refill(&self, receivers: &[SeccReceiver<Message>]) {
+        for (lane, receiver) in self.lanes.iter().zip(receivers) {
+            if let Some(buffer) = lane.buffer.as_ref() {
+                buffer.refill(receiver);
+            }
+        }
+    }
+
+    /// Lets the actor take the messages that it skipped in the overflow buffers again.
+    fn reset_skip(&self) {
+        for buffer in self.lanes.iter().filter_map(|lane| lane.buffer.as_ref()) {
+            buffer.reset_skip();
+        }
+    }
+
+    /// Wakes the next sender waiting for room in the lane at the index `lane`.
+    fn wake_one(&self, lane: usize) {
+        self.lanes[lane].waiters.wake_one();
+    }
+
+    /// Wakes all of the senders waiting for room in any of the lanes.
+    fn wake_all(&self) {
+        self.lanes.iter().for_each(|lane| lane.waiters.wake_all());
+    }
+}
+
+/// The wakers of the [`Aid::send_async`] futures waiting for room in a message channel of an
+/// actor, in the order that they started waiting. The actor wakes one every time it takes a
+/// message out of the channel and all of them when it stops.
+#[derive(Default)]
+struct SendWaiters {
+    /// The id to give to the next waker.
+    next: AtomicU64,
+    /// The number of waiting wakers, so that the actor can skip the lock if there are none.
+    len: AtomicUsize,
+    /// The waiting wakers and their ids.
+    wakers: Mutex<VecDeque<(u64, Waker)>>,
+}
+
+impl SendWaiters {
+    /// Adds the `waker` to the back of the queue, returning the id to cancel it with.
+    fn wait(&self, waker: &Waker) -> u64 {
+        let id = self.next.fetch_add(1, Ordering::Relaxed);
+        let mut wakers = self.wakers.lock().unwrap();
+        wakers.push_back((id, waker.clone()));
+        self.len.store(wakers.len(), Ordering::SeqCst);
+        id
+    }
+
+    /// Removes the waker with the `id`, returning false if it was woken already.
+    fn cancel(&self, id: u64) -> bool {
+        let mut wakers = self.wakers.lock().unwrap();
+        let waiting = match wakers.iter().position(|(i, _)| *i == id) {
+            Some(index) => wakers.remove(index).is_some(),
+            None => false,
+        };
+        self.len.store(wakers.len(), Ordering::SeqCst);
+        waiting
+    }
+
+    /// Wakes the waker that has waited the longest, if any.
+    fn wake_one(&self) {
+        if self.len.load(Ordering::SeqCst) == 0 {
+            return;
+        }
+        let mut wakers = self.wakers.lock().unwrap();
+        let waker = wakers.pop_front();
+        self.len.store(wakers.len(), Ordering::SeqCst);
+        drop(wakers);
+        if let Some((_, waker)) = waker {
+            waker.wake();
+        }
+    }
+
+    /// Wakes all of the wakers.
+    fn wake_all(&self) {
+        let wakers: Vec<_> = {
+            let mut wakers = self.wakers.lock().unwrap();
+            self.len.store(0, Ordering::SeqCst);
+            wakers.drain(..).collect()
+        };
+        wakers.into_iter().for_each(|(_, waker)| waker.wake());
+    }
+}
+
+/// The future returned by [`Aid::send_async`] for a local actor, which waits for room in the
+/// lane of the actor's mailbox that the message is sent to.
+struct SendAsync<'a> {
+    /// The actor to send the message to.
+    aid: &'a Aid,
+    /// Whether the actor is stopped.
+    stopped: &'a AtomicBool,
+    /// The lane of the actor's mailbox that the message is sent to.
+    lane: &'a Lane,
+    /// The actor system that the actor is on.
+    system: &'a ActorSystem,
+    /// The message, until it's sent.
+    message: Option<Message>,
+    /// The id of the waker of this future in the `waiters`, while it waits.
+    waiting: Option<u64>,
+}
+
+impl Future for SendAsync<'_> {
+    type Output = Result<(), AidError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        if let Some(id) = this.waiting.take() {
+            this.lane.waiters.cancel(id);
+        }
+        let message = this.message.take().expect("Polled after completion");
+        if this.stopped.load(Ordering::Relaxed) {
+            this.aid.dead_letter(message, this.system);
+            return Poll::Ready(Err(AidError::ActorAlreadyStopped));
+        }
+        let message = match this.lane.sender.send(message) {
+            Ok(_) => None,
+            Err(SeccErrors::Full(message)) => {
+                // Room may be made before the waker is added, so try again once it's added.
+                let id = this.lane.waiters.wait(cx.waker());
+                match this.lane.sender.send(message) {
+                    Ok(_) => {
+                        // Pass on the wake up that this future won't use, if it got one.
+                        if !this.lane.waiters.cancel(id) {
+                            this.lane.waiters.wake_one();
+                        }
+                        None
+                    }
+                    Err(SeccErrors::Full(message)) => {
+                        this.waiting = Some(id);
+                        Some(message)
+                    }
+                    Err(SeccErrors::Empty) => unreachable!("Sending can't fail with Empty"),
+                }
+            }
+            Err(SeccErrors::Empty) => unreachable!("Sending can't fail with Empty"),
+        };
+        match message {
+            Some(message) => {
+                this.message = Some(message);
+                Poll::Pending
+            }
+            None => {
+                if this.lane.sender.receivable() == 1 {
+                    this.system.schedule(this.aid.clone());
+                }
+                Poll::Ready(Ok(()))
+            }
+        }
+    }
+}
+
+impl Drop for SendAsync<'_> {
+    fn drop(&mut self) {
+        // Pass on the wake up that this future won't use, if it got one.
+        if let Some(id) = self.waiting {
+            if !self.lane.waiters.cancel(id) {
+                self.lane.waiters.wake_one();
+            }
+        }
+    }
+}
+
+/// The messages sent to an actor with a [`MailboxOverflow::DropOldest`] or
+/// [`MailboxOverflow::Unbounded`] mailbox that didn't fit in its message channel. The actor moves
+/// them into the channel, in the order that they were sent, as it takes messages out of it. Once
+/// the actor has skipped all of the messages in the channel, it takes the buffered messages
+/// straight out of the buffer instead.
+struct OverflowBuffer {
+    /// The send side of the actor's message channel.
+    sender: SeccSender<Message>,
+    /// The number of messages the mailbox holds if the oldest are dropped, `None` if it's
+    /// unbounded.
+    capacity: Option<usize>,
+    /// The number of buffered messages, so that the actor can skip the lock if there are none.
+    len: AtomicUsize,
+    /// The number of messages that the actor took straight out of the buffer.
+    taken: AtomicUsize,
+    /// The buffered messages and the number of messages to drop from the channel.
+    buffered: Mutex<Buffered>,
+}
+
+/// The state of an [`OverflowBuffer`], which is only changed while holding its lock.
+#[derive(Default)]
+struct Buffered {
+    /// The messages waiting for room in the channel, oldest first.
+    messages: VecDeque<Message>,
+    /// The number of the oldest messages in the channel that the actor drops before taking the
+    /// next one.
+    dropping: usize,
+    /// The number of buffered messages, from the oldest, that the actor skipped.
+    skipped: usize,
+}
+
+impl OverflowBuffer {
+    /// Creates the buffer for a channel that holds `capacity` messages, which drops the oldest
+    /// messages once another `capacity` messages are buffered if the `overflow` is `DropOldest`.
+    fn new(sender: SeccSender<Message>, capacity: u16, overflow: MailboxOverflow) -> Self {
+        OverflowBuffer {
+            sender,
+            capacity: if overflow == MailboxOverflow::DropOldest {
+                Some(capacity as usize)
+            } else {
+                None
+            },
+            len: AtomicUsize::new(0),
+            taken: AtomicUsize::new(0),
+            buffered: Mutex::new(Buffered::default()),
+        }
+    }
+
+    /// Sends the `message` to the channel, or buffers it if the channel is full or there are
+    /// buffered messages already that have to be sent first. Returns whether the message was
+    /// sent to the channel.
+    fn send(&self, message: Message) -> bool {
+        let mut buffered = self.buffered.lock().unwrap();
+        let message = if buffered.messages.is_empty() {
+            match self.sender.send(message) {
+                Ok(_) => return true,
+                Err(SeccErrors::Full(message)) => message,
+                Err(SeccErrors::Empty) => unreachable!("Sending can't fail with Empty"),
+            }
+        } else {
+            message
+        };
+        if let Some(capacity) = self.capacity {
+            // The oldest messages are dropped from the channel first and then from the buffer,
+            // leaving out the system messages.
+            if buffered.dropping < capacity {
+                buffered.dropping += 1;
+            } else if let Some(index) = buffered
+                .messages
+                .iter()
+                .position(|message| message.content_as::<SystemMsg>().is_none())
+            {
+                buffered.messages.remove(index);
+                if index < buffered.skipped {
+                    buffered.skipped -= 1;
+                }
+            }
+        }
+        buffered.messages.push_back(message);
+        self.len.store(buffered.messages.len(), Ordering::Release);
+        false
+    }
+
+    /// Drops the messages from the channel that were pushed out by newer ones and moves as many
+    /// buffered messages into the channel as fit. This is called by the actor before it takes
+    /// the next message out of the channel.
+    fn refill(&self, receiver: &SeccReceiver<Message>) {
+        if self.len.load(Ordering::Acquire) == 0 {
+            return;
+        }
+        let mut buffered = self.buffered.lock().unwrap();
+        let mut kept = Vec::new();
+        while buffered.dropping > 0 {
+            match receiver.receive() {
+                // System messages are never dropped, so they're buffered again instead.
+                Ok(message) if message.content_as::<SystemMsg>().is_some() => kept.push(message),
+                Ok(_) => buffered.dropping -= 1,
+                // The messages that the actor skipped are kept, so there may be fewer to drop.
+                Err(_) => buffered.dropping = 0,
+            }
+        }
+        let skipped = buffered.skipped;
+        for (index, message) in kept.into_iter().enumerate() {
+            buffered.messages.insert(skipped + index, message);
+        }
+        // Messages skipped in the buffer would be taken again in the channel, so they stay.
+        if buffered.skipped == 0 {
+            while let Some(message) = buffered.messages.pop_front() {
+                if let Err(SeccErrors::Full(message)) = self.sender.send(message) {
+                    buffered.messages.push_front(message);
+                    break;
+                }
+            }
+        }
+        self.len.store(buffered.messages.len(), Ordering::Release);
+    }
+
+    /// Peeks at the oldest buffered message that the actor hasn't skipped, if any.
+    fn peek(&self) -> Option<Message> {
+        if self.len.load(Ordering::Acquire) == 0 {
+            return None;
+        }
+        let buffered = self.buffered.lock().unwrap();
+        buffered.messages.get(buffered.skipped).cloned()
+    }
+
+    /// Removes the message returned by [`OverflowBuffer::peek`] from the buffer.
+    fn pop(&self) {
+        let mut buffered = self.buffered.lock().unwrap();
+        let skipped = buffered.skipped;
+        if buffered.messages.remove(skipped).is_some() {
+            self.taken.fetch_add(1, Ordering::Relaxed);
+        }
+        self.len.store(buffered.messages.len(), Ordering::Release);
+    }
+
+    /// Leaves the message returned by [`OverflowBuffer::peek`] in the buffer, moving on to the
+    /// next one.
+    fn skip(&self) {
+        let mut buffered = self.buffered.lock().unwrap();
+        buffered.skipped = (buffered.skipped + 1).min(buffered.messages.len());
+    }
+
+    /// Lets the actor take the messages that it skipped again.
+    fn reset_skip(&self) {
+        self.buffered.lock().unwrap().skipped = 0;
+    }
+}
+
 /// The inner data of an [`Aid`].
 ///
 /// This is kept separate to make serialization possible without duplicating all of the data
@@ -241,7 +735,7 @@ impl<'de> Deserialize<'de> for Aid {
                             uuid: serialized_form.uuid,
                             system_uuid: serialized_form.system_uuid,
                             name: serialized_form.name,
-                            sender: ActorSender::Remote { sender: sender },
+                            sender: ActorSender::Remote { sender, system },
                         }),
                     })
                 } else {
@@ -296,7 +790,9 @@ impl Aid {
     /// Attempts to send a message to the actor with the given [`Aid`] and returns
     /// `std::Result::Ok` when the send was successful or a `std::Result::Err<AxiomError>`
     /// if something went wrong with the send. Note that if a user just calls `send(msg).unwrap()`,
-    /// a panic could take down the dispatcher thread and thus eventually hang the process.
+    /// a panic could take down the dispatcher thread and thus eventually hang the process. What
+    /// happens when the actor's mailbox is full depends on the [`MailboxOverflow`] that the
+    /// actor was spawned with.
     ///
     /// # Examples
     /// ```
@@ -330,33 +826,175 @@ impl Aid {
         match &self.data.sender {
             ActorSender::Local {
                 stopped,
-                sender,
+                mailbox,
                 system,
+                overflow,
             } => {
                 if stopped.load(Ordering::Relaxed) {
+                    self.dead_letter(message, system);
                     Err(AidError::ActorAlreadyStopped)
                 } else {
-                    match sender.send_await_timeout(message, system.config().send_timeout) {
-                        Ok(_) => {
-                            if sender.receivable() == 1 {
-                                system.schedule(self.clone());
-                            };
-                            Ok(())
+                    let lane = mailbox.lane(&message);
+                    // System messages aren't dropped to make room, so they wait for it instead.
+                    let buffer = match lane.buffer.as_ref() {
+                        Some(_)
+                            if *overflow == MailboxOverflow::DropOldest
+                                && message.content_as::<SystemMsg>().is_some() =>
+                        {
+                            None
                         }
-                        Err(_) => Err(AidError::SendTimedOut(self.clone())),
+                        buffer => buffer,
+                    };
+                    let sent = match buffer {
+                        Some(buffer) => buffer.send(message),
+                        None => match lane.sender.send(message) {
+                            Ok(_) => true,
+                            Err(SeccErrors::Full(message)) => {
+                                self.overflow(message, *overflow, &lane.sender, system)?
+                            }
+                            Err(SeccErrors::Empty) => unreachable!("Sending can't fail with Empty"),
+                        },
+                    };
+                    // The actor may have skipped everything in the channel, in which case it
+                    // takes a buffered message straight out of the buffer.
+                    if (sent && lane.sender.receivable() == 1) || (!sent && buffer.is_some()) {
+                        system.schedule(self.clone());
                     }
+                    Ok(())
                 }
             }
-            ActorSender::Remote { sender } => {
-                sender
-                    .send_await(WireMessage::ActorMessage {
+            ActorSender::Remote { sender, .. } => {
+                let reply_to = message.reply_to();
+                let priority = message.priority();
+                let wire_message = if reply_to.is_some() || priority != MessagePriority::Normal {
+                    WireMessage::ActorEnvelope {
                         actor_uuid: self.data.uuid,
                         system_uuid: self.data.system_uuid,
                         message,
-                    })
-                    .unwrap();
+                        reply_to,
+                        priority,
+                    }
+                } else {
+                    WireMessage::ActorMessage {
+                        actor_uuid: self.data.uuid,
+                        system_uuid: self.data.system_uuid,
+                        message,
+                    }
+                };
+                sender.send_await(wire_message).unwrap();
                 Ok(())
             }
+            ActorSender::Reply { .. } => {
+                if let Some(sender) = self.take_reply_sender() {
+                    // The asker may have given up on the reply so the error is ignored.
+                    let _ = sender.send(message);
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Handles a `message` sent while the lane of the actor's mailbox that it's sent to is full,
+    /// as set by its `overflow`, returning whether the message was sent to the lane after all.
+    /// System messages are always waited for room for, so that they aren't lost.
+    fn overflow(
+        &self,
+        message: Message,
+        overflow: MailboxOverflow,
+        sender: &SeccSender<Message>,
+        system: &ActorSystem,
+    ) -> Result<bool, AidError> {
+        let overflow = match message.content_as::<SystemMsg>() {
+            Some(_) => MailboxOverflow::Block,
+            None => overflow,
+        };
+        match overflow {
+            MailboxOverflow::Block => {
+                match sender.send_await_timeout(message, system.config().send_timeout) {
+                    Ok(_) => Ok(true),
+                    Err(_) => Err(AidError::SendTimedOut(self.clone())),
+                }
+            }
+            MailboxOverflow::FailFast => Err(AidError::MailboxFull(self.clone())),
+            MailboxOverflow::DropNewest => {
+                trace!("Actor {} has a full mailbox, dropping message", self);
+                Ok(false)
+            }
+            MailboxOverflow::DeadLetter => {
+                let reason = DeadLetterReason::MailboxFull;
+                system.dead_letter(message, self.uuid(), Some(self.clone()), reason);
+                Ok(false)
+            }
+            MailboxOverflow::DropOldest | MailboxOverflow::Unbounded => {
+                unreachable!("Buffered mailboxes don't overflow")
+            }
+        }
+    }
+
+    /// Sends a message to the actor like [`Aid::send`], but rather than blocking the thread
+    /// while the message channel of the actor is full, the returned future waits until the actor
+    /// takes a message out of the channel. This lets backpressure from a busy actor propagate to
+    /// the actors sending to it without holding up the threads of the actor system. Senders
+    /// waiting on the same actor send their messages in the order that they started waiting. If
+    /// the actor stops while the future waits, it resolves to [`AidError::ActorAlreadyStopped`]
+    /// and the message goes to the dead letters. Messages for actors on other actor systems, and
+    /// for actors spawned with a [`MailboxOverflow`] other than `Block`, are sent like
+    /// [`Aid::send`] does.
+    ///
+    /// # Examples
+    /// ```
+    /// use axiom::prelude::*;
+    ///
+    /// let config = ActorSystemConfig::default().message_channel_size(1);
+    /// let system = ActorSystem::create(config);
+    ///
+    /// let consumer = system
+    ///     .spawn()
+    ///     .with(0 as usize, |count: usize, context: Context, message: Message| async move {
+    ///         if let Some(_) = message.content_as::<usize>() {
+    ///             if count == 99 {
+    ///                 context.system.trigger_shutdown();
+    ///             }
+    ///             return Ok(Status::done(count + 1));
+    ///         }
+    ///         Ok(Status::done(count))
+    ///     })
+    ///     .unwrap();
+    ///
+    /// let producer = system
+    ///     .spawn()
+    ///     .with(consumer, |consumer: Aid, _: Context, message: Message| async move {
+    ///         if let Some(_) = message.content_as::<String>() {
+    ///             for i in 0..100 as usize {
+    ///                 consumer.send_async(Message::new(i)).await?;
+    ///             }
+    ///         }
+    ///         Ok(Status::done(consumer))
+    ///     })
+    ///     .unwrap();
+    ///
+    /// producer.send_new("produce".to_string()).unwrap();
+    /// system.await_shutdown(None);
+    /// ```
+    pub async fn send_async(&self, message: Message) -> Result<(), AidError> {
+        match &self.data.sender {
+            ActorSender::Local {
+                stopped,
+                mailbox,
+                system,
+                overflow: MailboxOverflow::Block,
+            } => {
+                let future = SendAsync {
+                    aid: self,
+                    stopped,
+                    lane: mailbox.lane(&message),
+                    system,
+                    message: Some(message),
+                    waiting: None,
+                };
+                future.await
+            }
+            _ => self.send(message),
         }
     }
 
@@ -445,8 +1083,11 @@ impl Aid {
     /// rather that _at least_ the duration will pass before the message is sent to the actor.
     /// Axiom will try to send as close as possible without going under the amount but precise
     /// timing should not be depended on.  This method will return an `Err` if the actor has been
-    /// stopped or `Ok` if the message was scheduled to be sent. If the actor is stopped before
-    /// the duration passes then the scheduled message will never get to the actor.
+    /// stopped or `Ok` with a [`ScheduleHandle`] that can be used to cancel the message if the
+    /// message was scheduled to be sent. If the actor is stopped before the duration passes then
+    /// the scheduled message will never get to the actor. The message is scheduled on the actor
+    /// system of the caller, even if the actor is on a remote actor system, so that it can be
+    /// cancelled.
     ///
     /// # Examples
     /// ```
@@ -476,33 +1117,89 @@ impl Aid {
     ///
     /// system.await_shutdown(None);
     /// ```
-    pub fn send_after(&self, message: Message, duration: Duration) -> Result<(), AidError> {
+    pub fn send_after(
+        &self,
+        message: Message,
+        duration: Duration,
+    ) -> Result<ScheduleHandle, AidError> {
+        let system = self.system_if_running(&message)?;
+        Ok(system.send_after(message, self.clone(), duration))
+    }
+
+    /// Schedules the given message to be sent to the actor every `interval`, starting one
+    /// `interval` from now, until the returned [`ScheduleHandle`] is cancelled or the actor
+    /// stops. The messages are sent at a fixed rate so that any delay in sending one of them
+    /// doesn't push back the ones after it, with the same best attempt at timing as
+    /// [`Aid::send_after`]. This method will return an `Err` if the actor has been stopped.
+    /// Note that passing an interval of zero will cause a panic.
+    ///
+    /// # Examples
+    /// ```
+    /// use axiom::prelude::*;
+    /// use std::time::Duration;
+    ///
+    /// let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+    ///
+    /// let aid = system
+    ///     .spawn()
+    ///     .with(
+    ///         0 as usize,
+    ///         |beats: usize, context: Context, message: Message| async move {
+    ///             if let Some(_) = message.content_as::<String>() {
+    ///                 if beats == 2 {
+    ///                     context.system.trigger_shutdown();
+    ///                 }
+    ///                 return Ok(Status::done(beats + 1));
+    ///             }
+    ///             Ok(Status::done(beats))
+    ///        },
+    ///     )
+    ///     .unwrap();
+    ///
+    /// let heartbeat = Message::new("heartbeat".to_string());
+    /// let handle = aid.send_interval(heartbeat, Duration::from_millis(1)).unwrap();
+    ///
+    /// system.await_shutdown(None);
+    /// handle.cancel();
+    /// ```
+    pub fn send_interval(
+        &self,
+        message: Message,
+        interval: Duration,
+    ) -> Result<ScheduleHandle, AidError> {
+        let system = self.system_if_running(&message)?;
+        Ok(system.send_interval(message, self.clone(), interval))
+    }
+
+    /// Shortcut for calling `send_interval(Message::new(value))`. See [`Aid::send_interval`]
+    /// for more information.
+    pub fn send_new_interval<T>(
+        &self,
+        value: T,
+        interval: Duration,
+    ) -> Result<ScheduleHandle, AidError>
+    where
+        T: 'static + ActorMessage,
+    {
+        self.send_interval(Message::new(value), interval)
+    }
+
+    /// Returns the actor system to schedule a message for the actor on, or an error if the actor
+    /// has already stopped, in which case the message goes to the dead letters.
+    fn system_if_running(&self, message: &Message) -> Result<&ActorSystem, AidError> {
         match &self.data.sender {
             ActorSender::Local {
                 stopped, system, ..
             } => {
                 if stopped.load(Ordering::Relaxed) {
+                    self.dead_letter(message.clone(), system);
                     Err(AidError::ActorAlreadyStopped)
                 } else {
-                    system.send_after(message, self.clone(), duration);
-                    Ok(())
+                    Ok(system)
                 }
             }
-            ActorSender::Remote { sender } => {
-                if let Err(err) = sender.send_await(WireMessage::DelayedActorMessage {
-                    duration,
-                    actor_uuid: self.data.uuid,
-                    system_uuid: self.data.system_uuid,
-                    message,
-                }) {
-                    // Right now, this is the full extent of errors, but if that should change, it
-                    // should create a compiler error.
-                    return match err {
-                        SeccErrors::Full(_) | SeccErrors::Empty => Ok(()),
-                    };
-                }
-                Ok(())
-            }
+            ActorSender::Remote { system, .. } => Ok(system),
+            ActorSender::Reply { system, .. } => Ok(system),
         }
     }
 
@@ -540,7 +1237,11 @@ impl Aid {
     ///
     /// system.await_shutdown(None);
     /// ```
-    pub fn send_arc_after<T>(&self, value: Arc<T>, duration: Duration) -> Result<(), AidError>
+    pub fn send_arc_after<T>(
+        &self,
+        value: Arc<T>,
+        duration: Duration,
+    ) -> Result<ScheduleHandle, AidError>
     where
         T: 'static + ActorMessage,
     {
@@ -581,21 +1282,183 @@ impl Aid {
     ///
     /// system.await_shutdown(None);
     /// ```
-    pub fn send_new_after<T>(&self, value: T, duration: Duration) -> Result<(), AidError>
+    pub fn send_new_after<T>(
+        &self,
+        value: T,
+        duration: Duration,
+    ) -> Result<ScheduleHandle, AidError>
     where
         T: 'static + ActorMessage,
     {
         self.send_after(Message::new(value), duration)
     }
 
-    /// The unique UUID for this actor within the entire cluster. The UUID for an [`Aid`]
-    /// is generated with a v4 random UUID so the chances of collision are not worth considering.
-    #[inline]
-    pub fn uuid(&self) -> Uuid {
-        self.data.uuid.clone()
+    /// Sends the message to the actor and returns a future that resolves to the reply of type
+    /// `R`. The actor replies by sending the reply to the [`Aid`] returned by
+    /// [`Message::reply_to`] on the message it received. The reply is received by a lightweight
+    /// temporary endpoint rather than an actor, so asking is cheap. If no reply arrives within
+    /// the `timeout` the future resolves to [`AskError::TimedOut`] and if the actor stops before
+    /// replying it resolves to [`AskError::ActorStopped`]. Replies that arrive after the future
+    /// has resolved are discarded.
+    ///
+    /// # Examples
+    /// ```
+    /// use axiom::prelude::*;
+    /// use std::time::Duration;
+    ///
+    /// let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+    ///
+    /// let doubler = system
+    ///     .spawn()
+    ///     .with((), |_: (), _: Context, message: Message| async move {
+    ///         if let Some(value) = message.content_as::<i32>() {
+    ///             message.reply_to().unwrap().send_new(*value * 2)?;
+    ///         }
+    ///         Ok(Status::done(()))
+    ///     })
+    ///     .unwrap();
+    ///
+    /// let asker = system
+    ///     .spawn()
+    ///     .with(doubler, |doubler: Aid, context: Context, message: Message| async move {
+    ///         if let Some(value) = message.content_as::<i32>() {
+    ///             let timeout = Duration::from_millis(1000);
+    ///             let reply = doubler.ask::<i32>(Message::new(*value), timeout).await?;
+    ///             assert_eq!(*value * 2, *reply);
+    ///             context.system.trigger_shutdown();
+    ///         }
+    ///         Ok(Status::done(doubler))
+    ///     })
+    ///     .unwrap();
+    ///
+    /// asker.send_new(11).unwrap();
+    /// system.await_shutdown(None);
+    /// ```
+    pub async fn ask<R>(&self, message: Message, timeout: Duration) -> Result<Arc<R>, AskError>
+    where
+        R: 'static + ActorMessage,
+    {
+        let system = match &self.data.sender {
+            ActorSender::Local { system, .. } => system.clone(),
+            ActorSender::Remote { system, .. } => system.clone(),
+            ActorSender::Reply { system, .. } => system.clone(),
+        };
+        let (reply_to, receiver) = Aid::reply_endpoint(&system, self);
+        if let Err(error) = self.send(message.with_reply_to(reply_to.clone())) {
+            reply_to.take_reply_sender();
+            return Err(AskError::SendFailed(error));
+        }
+        system.monitor(&reply_to, self);
+        // A quick reply may have removed the endpoint before the monitor was added, in which
+        // case nobody else will remove the monitor.
+        if reply_to.is_reply_taken() {
+            system.demonitor(&reply_to, self);
+        }
+        let timeout = system.send_after(Message::new(AskTimedOut), reply_to, timeout);
+
+        // The endpoint is only dropped without sending if the actor system drops it.
+        let reply = receiver.await.map_err(|_| AskError::ActorStopped);
+        timeout.cancel();
+        let reply = reply?;
+        if reply.content_as::<AskTimedOut>().is_some() {
+            return Err(AskError::TimedOut);
+        }
+        if let Some(msg) = reply.content_as::<SystemMsg>() {
+            if let SystemMsg::Stopped { aid, .. } = &*msg {
+                if aid == self {
+                    return Err(AskError::ActorStopped);
+                }
+            }
+        }
+        reply.content_as::<R>().ok_or(AskError::UnexpectedReply)
     }
 
-    /// The unique UUID for the actor system that this actor lives on. As with `uuid` this value
+    /// A blocking version of [`Aid::ask`] for use from threads outside of the actor system. This
+    /// must not be called from within an actor's processor as it will block the thread that is
+    /// running the actor; use [`Aid::ask`] there instead.
+    ///
+    /// # Examples
+    /// ```
+    /// use axiom::prelude::*;
+    /// use std::time::Duration;
+    ///
+    /// let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+    ///
+    /// let aid = system
+    ///     .spawn()
+    ///     .with((), |_: (), _: Context, message: Message| async move {
+    ///         if let Some(value) = message.content_as::<String>() {
+    ///             message.reply_to().unwrap().send_new(value.len())?;
+    ///         }
+    ///         Ok(Status::done(()))
+    ///     })
+    ///     .unwrap();
+    ///
+    /// let timeout = Duration::from_millis(1000);
+    /// let reply = aid.ask_blocking::<usize>(Message::new("Hello".to_string()), timeout);
+    /// assert_eq!(5, *reply.unwrap());
+    /// ```
+    pub fn ask_blocking<R>(&self, message: Message, timeout: Duration) -> Result<Arc<R>, AskError>
+    where
+        R: 'static + ActorMessage,
+    {
+        futures::executor::block_on(self.ask(message, timeout))
+    }
+
+    /// Creates a reply endpoint for asking the `asked` actor and registers it on the system so
+    /// that remote actors can reply to it. Returns the [`Aid`] of the endpoint along with the
+    /// receiver that the first message sent to the endpoint will be delivered on.
+    fn reply_endpoint(system: &ActorSystem, asked: &Aid) -> (Aid, oneshot::Receiver<Message>) {
+        let (sender, receiver) = oneshot::channel();
+        let aid = Aid {
+            data: Arc::new(AidData {
+                uuid: Uuid::new_v4(),
+                system_uuid: system.uuid(),
+                name: None,
+                sender: ActorSender::Reply {
+                    sender: Mutex::new(Some(sender)),
+                    asked: asked.clone(),
+                    system: system.clone(),
+                },
+            }),
+        };
+        system.register_reply_endpoint(&aid);
+        (aid, receiver)
+    }
+
+    /// Takes the sender out of a reply endpoint and removes the endpoint from the system so
+    /// that it cannot be sent to again. Returns `None` if the sender was already taken.
+    fn take_reply_sender(&self) -> Option<oneshot::Sender<Message>> {
+        if let ActorSender::Reply {
+            sender,
+            asked,
+            system,
+        } = &self.data.sender
+        {
+            let sender = sender.lock().unwrap().take()?;
+            system.deregister_reply_endpoint(self, asked);
+            Some(sender)
+        } else {
+            None
+        }
+    }
+
+    /// Checks if the sender of a reply endpoint has been taken by a message sent to it.
+    fn is_reply_taken(&self) -> bool {
+        match &self.data.sender {
+            ActorSender::Reply { sender, .. } => sender.lock().unwrap().is_none(),
+            _ => false,
+        }
+    }
+
+    /// The unique UUID for this actor within the entire cluster. The UUID for an [`Aid`]
+    /// is generated with a v4 random UUID so the chances of collision are not worth considering.
+    #[inline]
+    pub fn uuid(&self) -> Uuid {
+        self.data.uuid.clone()
+    }
+
+    /// The unique UUID for the actor system that this actor lives on. As with `uuid` this value
     /// is a v4 UUID and so the chances of two systems having the same uuid is inconsequential.
     #[inline]
     pub fn system_uuid(&self) -> Uuid {
@@ -625,10 +1488,9 @@ impl Aid {
     /// efficiently through passing [`Arc`]s.
     #[inline]
     pub fn is_local(&self) -> bool {
-        if let ActorSender::Local { .. } = self.data.sender {
-            true
-        } else {
-            false
+        match self.data.sender {
+            ActorSender::Local { .. } | ActorSender::Reply { .. } => true,
+            ActorSender::Remote { .. } => false,
         }
     }
 
@@ -636,7 +1498,7 @@ impl Aid {
     /// for local [`Aid`]s, remote [`Aid`]s will return an error if this is called.
     pub fn sent(&self) -> Result<usize, AidError> {
         match &self.data.sender {
-            ActorSender::Local { sender, .. } => Ok(sender.sent()),
+            ActorSender::Local { mailbox, .. } => Ok(mailbox.sent()),
             _ => Err(AidError::AidNotLocal),
         }
     }
@@ -645,7 +1507,7 @@ impl Aid {
     /// for local [`Aid`]s, remote [`Aid`]s will return an error if this is called.
     pub fn received(&self) -> Result<usize, AidError> {
         match &self.data.sender {
-            ActorSender::Local { sender, .. } => Ok(sender.received()),
+            ActorSender::Local { mailbox, .. } => Ok(mailbox.received()),
             _ => Err(AidError::AidNotLocal),
         }
     }
@@ -665,6 +1527,21 @@ impl Aid {
         }
     }
 
+    /// Determines if the actor referenced by the [`Aid`] has been marked as stopped. This only
+    /// works for local [`Aid`]s, for any other [`Aid`] this returns `false`.
+    pub(crate) fn is_stopped(&self) -> bool {
+        match &self.data.sender {
+            ActorSender::Local { stopped, .. } => stopped.load(Ordering::Acquire),
+            _ => false,
+        }
+    }
+
+    /// Sends a message that couldn't be delivered to this actor to the dead letters.
+    fn dead_letter(&self, message: Message, system: &ActorSystem) {
+        let reason = DeadLetterReason::ActorStopped;
+        system.dead_letter(message, self.uuid(), Some(self.clone()), reason);
+    }
+
     /// Checks to see if the left and right aid actually point at the exact same actor.
     pub fn ptr_eq(left: &Aid, right: &Aid) -> bool {
         Arc::ptr_eq(&left.data, &right.data)
@@ -700,12 +1577,549 @@ impl Hash for Aid {
     }
 }
 
+/// A typed wrapper around an [`Aid`] that only allows sending messages of the type `M` which
+/// is the protocol of the actor.
+///
+/// A `TypedAid` is created by spawning an actor with [`ActorBuilder::with_typed`]. Sending
+/// any other type of message is a compile time error rather than a message that the actor
+/// doesn't know how to handle at runtime. The typed [`Aid`] serializes exactly like the
+/// untyped one so it can be sent to remote actors as well, and the untyped [`Aid`] is always
+/// available for monitoring and other operations that don't involve the protocol.
+///
+/// # Examples
+/// ```
+/// use axiom::prelude::*;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// enum Counter {
+///     Add(usize),
+///     Reset,
+/// }
+///
+/// async fn counter(count: usize, _: Context, message: Message) -> ActorResult<usize> {
+///     match message.content_as::<Counter>().as_deref() {
+///         Some(Counter::Add(value)) => Ok(Status::done(count + value)),
+///         Some(Counter::Reset) => Ok(Status::done(0)),
+///         None => Ok(Status::done(count)),
+///     }
+/// }
+///
+/// let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+/// let aid: TypedAid<Counter> = system.spawn().with_typed(0, counter).unwrap();
+/// aid.send(Counter::Add(11)).unwrap();
+/// aid.send(Counter::Reset).unwrap();
+/// ```
+///
+/// Sending a message that isn't part of the protocol won't compile:
+/// ```compile_fail
+/// # use axiom::prelude::*;
+/// # async fn counter(count: usize, _: Context, _: Message) -> ActorResult<usize> {
+/// #     Ok(Status::done(count))
+/// # }
+/// let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+/// let aid: TypedAid<usize> = system.spawn().with_typed(0, counter).unwrap();
+/// aid.send("Not a usize".to_string()).unwrap();
+/// ```
+pub struct TypedAid<M> {
+    /// The untyped [`Aid`] that messages are sent with.
+    aid: Aid,
+    /// Marks the type of message that the actor accepts without owning one.
+    message: PhantomData<fn(M)>,
+}
+
+impl<M> TypedAid<M>
+where
+    M: 'static + ActorMessage,
+{
+    /// Sends the value to the actor. See [`Aid::send_new`] for more information.
+    pub fn send(&self, value: M) -> Result<(), AidError> {
+        self.aid.send_new(value)
+    }
+
+    /// Sends the value to the actor, waiting for room in the message channel of the actor
+    /// without blocking the thread. See [`Aid::send_async`] for more information.
+    pub async fn send_async(&self, value: M) -> Result<(), AidError> {
+        self.aid.send_async(Message::new(value)).await
+    }
+
+    /// Sends the value that is already in an [`Arc`] to the actor. See [`Aid::send_arc`] for
+    /// more information.
+    pub fn send_arc(&self, value: Arc<M>) -> Result<(), AidError> {
+        self.aid.send_arc(value)
+    }
+
+    /// Sends the value to the actor after the duration has elapsed. See
+    /// [`Aid::send_new_after`] for more information.
+    pub fn send_after(&self, value: M, duration: Duration) -> Result<ScheduleHandle, AidError> {
+        self.aid.send_new_after(value, duration)
+    }
+
+    /// Sends the value to the actor every `interval` until the schedule is cancelled or the
+    /// actor stops. See [`Aid::send_interval`] for more information.
+    pub fn send_interval(&self, value: M, interval: Duration) -> Result<ScheduleHandle, AidError> {
+        self.aid.send_new_interval(value, interval)
+    }
+
+    /// Sends the value to the actor and returns a future that resolves to the reply of type
+    /// `R`. See [`Aid::ask`] for more information.
+    pub async fn ask<R>(&self, value: M, timeout: Duration) -> Result<Arc<R>, AskError>
+    where
+        R: 'static + ActorMessage,
+    {
+        self.aid.ask(Message::new(value), timeout).await
+    }
+
+    /// A blocking version of [`TypedAid::ask`]. See [`Aid::ask_blocking`] for more information.
+    pub fn ask_blocking<R>(&self, value: M, timeout: Duration) -> Result<Arc<R>, AskError>
+    where
+        R: 'static + ActorMessage,
+    {
+        self.aid.ask_blocking(Message::new(value), timeout)
+    }
+
+    /// Returns the untyped [`Aid`] of the actor.
+    pub fn aid(&self) -> &Aid {
+        &self.aid
+    }
+}
+
+impl<M> From<TypedAid<M>> for Aid {
+    fn from(typed: TypedAid<M>) -> Aid {
+        typed.aid
+    }
+}
+
+impl<M> Clone for TypedAid<M> {
+    fn clone(&self) -> Self {
+        TypedAid {
+            aid: self.aid.clone(),
+            message: PhantomData,
+        }
+    }
+}
+
+impl<M> Serialize for TypedAid<M> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.aid.serialize(serializer)
+    }
+}
+
+impl<'de, M> Deserialize<'de> for TypedAid<M> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(TypedAid {
+            aid: Aid::deserialize(deserializer)?,
+            message: PhantomData,
+        })
+    }
+}
+
+impl<M> std::cmp::PartialEq for TypedAid<M> {
+    fn eq(&self, other: &Self) -> bool {
+        self.aid == other.aid
+    }
+}
+
+impl<M> std::cmp::Eq for TypedAid<M> {}
+
+impl<M> Hash for TypedAid<M> {
+    fn hash<H: Hasher>(&self, state: &'_ mut H) {
+        self.aid.hash(state);
+    }
+}
+
+impl<M> std::fmt::Debug for TypedAid<M> {
+    fn fmt(&self, formatter: &'_ mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(formatter, "TypedAid{{aid: {:?}}}", self.aid)
+    }
+}
+
+impl<M> std::fmt::Display for TypedAid<M> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.aid)
+    }
+}
+
+/// A handle to a future spawned with [`Context::spawn_task`] or [`Context::pipe_to`] that can
+/// be used to cancel the future. Dropping the handle doesn't cancel the future.
+#[derive(Clone, Debug)]
+pub struct TaskHandle {
+    /// The [`Aid`] of the actor running the future.
+    aid: Aid,
+    /// Used to abort the future when the task is cancelled.
+    abort: AbortHandle,
+}
+
+impl TaskHandle {
+    /// Cancels the task, dropping the future unless it has already completed. A cancelled
+    /// [`Context::pipe_to`] task doesn't send a message.
+    pub fn cancel(&self) {
+        self.abort.abort();
+    }
+
+    /// Determines if the task has finished, either because its future completed or because
+    /// the task was cancelled.
+    pub fn is_finished(&self) -> bool {
+        self.aid.is_stopped()
+    }
+}
+
+/// The messages that an actor stashed with [`Context::stash`] to process later, which is shared
+/// by all of the contexts of the actor.
+#[derive(Default)]
+pub(crate) struct Stash {
+    /// The number of messages that the stash holds, whether stashed or unstashed.
+    capacity: usize,
+    /// The stashed messages, in the order that they were stashed.
+    stashed: VecDeque<Message>,
+    /// The unstashed messages that the actor has yet to take, in the order that they were
+    /// stashed.
+    unstashed: VecDeque<Message>,
+}
+
+impl Stash {
+    /// Creates an empty stash that holds up to `capacity` messages.
+    fn new(capacity: usize) -> Stash {
+        Stash {
+            capacity,
+            ..Default::default()
+        }
+    }
+}
+
+impl std::fmt::Debug for Stash {
+    fn fmt(&self, formatter: &'_ mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter
+            .debug_struct("Stash")
+            .field("capacity", &self.capacity)
+            .field("stashed", &self.stashed.len())
+            .field("unstashed", &self.unstashed.len())
+            .finish()
+    }
+}
+
+/// The state of the actor running a future spawned with [`Context::spawn_task`], which holds
+/// the future until the actor starts.
+type TaskState = Mutex<Option<BoxFuture<'static, ()>>>;
+
 /// A context that is passed to the processor to give immutable access to elements of the actor
 /// system to the implementor of an actor's processor.
 #[derive(Clone, Debug)]
 pub struct Context {
     pub aid: Aid,
     pub system: ActorSystem,
+    /// Where to reply to the message being processed. See [`Context::reply_to`].
+    reply_to: Option<Aid>,
+    /// The receive timeout of the actor, shared by all of its contexts. See
+    /// [`Context::set_receive_timeout`].
+    receive_timeout: Arc<Mutex<Option<Duration>>>,
+    /// The messages stashed by the actor, shared by all of its contexts. See
+    /// [`Context::stash`].
+    stash: Arc<Mutex<Stash>>,
+}
+
+impl Context {
+    /// Returns the [`Aid`] that a reply to the message being processed should be sent to. This
+    /// is the same as [`Message::reply_to`] for the message and is here for actors built from
+    /// [`Handler`]s, which get the content of the message rather than the message itself.
+    pub fn reply_to(&self) -> Option<Aid> {
+        self.reply_to.clone()
+    }
+
+    /// Creates a builder for spawning a child of this actor. Children are stopped before their
+    /// parent when the parent stops and their names are scoped under the name of the parent, so
+    /// a child named `worker` of an actor named `orders` is registered as `orders/worker`. If
+    /// the parent has no name, its uuid is used in place of the name. See
+    /// [`ActorSystem::spawn`] for more information on using the builder.
+    pub fn spawn(&self) -> ActorBuilder {
+        let mut builder = self.system.spawn();
+        builder.parent = Some(self.aid.clone());
+        builder
+    }
+
+    /// Returns the receive timeout of the actor, if it has one.
+    pub fn receive_timeout(&self) -> Option<Duration> {
+        *self.receive_timeout.lock().unwrap()
+    }
+
+    /// Stashes the `message` to process it later, after calling [`Context::unstash_all`]. This
+    /// is an alternative to returning [`Status::Skip`] that moves the message out of the
+    /// actor's mailbox, so that deferred messages don't take up room that senders need. The
+    /// processor should stash the message that it's processing and return [`Status::Done`].
+    /// Stashing fails with [`StashError::Full`] if the stash already holds as many messages as
+    /// set with [`ActorBuilder::stash_capacity`]. The stashed messages are dropped when the
+    /// actor stops.
+    ///
+    /// # Examples
+    /// ```
+    /// use axiom::prelude::*;
+    ///
+    /// let system = ActorSystem::create(ActorSystemConfig::default());
+    ///
+    /// // Defers the numbers until it's sent `true`.
+    /// let aid = system
+    ///     .spawn()
+    ///     .stash_capacity(100)
+    ///     .with(false, |open: bool, context: Context, message: Message| async move {
+    ///         if let Some(value) = message.content_as::<i32>() {
+    ///             if !open {
+    ///                 context.stash(message)?;
+    ///                 return Ok(Status::done(open));
+    ///             }
+    ///             println!("{}", value);
+    ///         } else if let Some(open) = message.content_as::<bool>() {
+    ///             if *open {
+    ///                 context.unstash_all();
+    ///             }
+    ///             return Ok(Status::done(*open));
+    ///         }
+    ///         Ok(Status::done(open))
+    ///     })
+    ///     .unwrap();
+    ///
+    /// aid.send_new(1).unwrap();
+    /// aid.send_new(2).unwrap();
+    /// aid.send_new(true).unwrap();
+    /// system.trigger_and_await_shutdown(None);
+    /// ```
+    pub fn stash(&self, message: Message) -> Result<(), StashError> {
+        let mut stash = self.stash.lock().unwrap();
+        if stash.stashed.len() + stash.unstashed.len() >= stash.capacity {
+            return Err(StashError::Full);
+        }
+        stash.stashed.push_back(message);
+        Ok(())
+    }
+
+    /// Returns the stashed messages to the actor, which takes them in the order that they were
+    /// stashed once it's done with the message that it's processing. They are taken before the
+    /// messages in the actor's mailbox, apart from the system messages of a priority mailbox.
+    /// An unstashed message that the actor skips with [`Status::Skip`] is stashed again.
+    pub fn unstash_all(&self) {
+        let mut stash = self.stash.lock().unwrap();
+        let Stash {
+            stashed, unstashed, ..
+        } = &mut *stash;
+        unstashed.extend(stashed.drain(..));
+    }
+
+    /// Returns the number of messages that the actor has stashed and not unstashed.
+    pub fn stashed(&self) -> usize {
+        self.stash.lock().unwrap().stashed.len()
+    }
+
+    /// Returns a future that completes after the `duration`. The future is driven by the timer
+    /// of the actor system and wakes the actor when it completes, so the actor can await a
+    /// delay without blocking the thread that it runs on. Note that the actor doesn't process
+    /// other messages while it awaits.
+    ///
+    /// # Examples
+    /// ```
+    /// use axiom::prelude::*;
+    /// use std::time::Duration;
+    ///
+    /// let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+    ///
+    /// let aid = system
+    ///     .spawn()
+    ///     .with((), |_: (), context: Context, message: Message| async move {
+    ///         if let Some(_) = message.content_as::<i32>() {
+    ///             context.sleep(Duration::from_millis(10)).await;
+    ///             context.system.trigger_shutdown();
+    ///         }
+    ///         Ok(Status::done(()))
+    ///     })
+    ///     .unwrap();
+    ///
+    /// aid.send_new(11).unwrap();
+    /// system.await_shutdown(None);
+    /// ```
+    pub fn sleep(&self, duration: Duration) -> Sleep {
+        self.system.sleep(duration)
+    }
+
+    /// Returns a future that completes with the output of the `future` if it completes within
+    /// the `duration`, or with a [`TimeoutError`] otherwise, in which case the `future` is
+    /// dropped. Like [`Context::sleep`], the timeout is driven by the timer of the actor system.
+    ///
+    /// # Examples
+    /// ```
+    /// use axiom::prelude::*;
+    /// use std::time::Duration;
+    ///
+    /// let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+    ///
+    /// let aid = system
+    ///     .spawn()
+    ///     .with((), |_: (), context: Context, message: Message| async move {
+    ///         if let Some(_) = message.content_as::<i32>() {
+    ///             let slow = context.sleep(Duration::from_secs(60));
+    ///             let result = context.timeout(Duration::from_millis(10), slow).await;
+    ///             assert_eq!(Err(TimeoutError), result);
+    ///             context.system.trigger_shutdown();
+    ///         }
+    ///         Ok(Status::done(()))
+    ///     })
+    ///     .unwrap();
+    ///
+    /// aid.send_new(11).unwrap();
+    /// system.await_shutdown(None);
+    /// ```
+    pub fn timeout<F: Future>(&self, duration: Duration, future: F) -> Timeout<F> {
+        Timeout::new(future, self.sleep(duration))
+    }
+
+    /// Spawns the `future` to run on the actor system independently of the actor, so that the
+    /// actor can keep processing messages while the future runs. The future is run by a
+    /// lightweight actor that stops when the future completes. The task isn't stopped when the
+    /// actor stops; use the returned [`TaskHandle`] to cancel it.
+    pub fn spawn_task<F>(&self, future: F) -> Result<TaskHandle, SystemError>
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        self.spawn_task_on(future, false)
+    }
+
+    /// Spawns the `closure` to run on the blocking threads of the actor system and returns a
+    /// future that completes with its result, so that an actor can run blocking code without
+    /// holding up the threads that run the other actors. The closure starts running right
+    /// away, even if the future isn't awaited. If the closure panics, the future completes with
+    /// a [`Panic`], and if the actor system shuts down before the closure runs, the future
+    /// completes with a `Canceled` error.
+    ///
+    /// # Examples
+    /// ```
+    /// use axiom::prelude::*;
+    ///
+    /// let system = ActorSystem::create(ActorSystemConfig::default().blocking_pool_size(2));
+    ///
+    /// let aid = system
+    ///     .spawn()
+    ///     .with((), |_: (), context: Context, message: Message| async move {
+    ///         if let Some(path) = message.content_as::<String>() {
+    ///             let path = path.to_string();
+    ///             let read = context.spawn_blocking(move || std::fs::read_to_string(path));
+    ///             let manifest = read.await??;
+    ///             assert!(manifest.contains("[package]"));
+    ///             context.system.trigger_shutdown();
+    ///         }
+    ///         Ok(Status::done(()))
+    ///     })
+    ///     .unwrap();
+    ///
+    /// aid.send_new("Cargo.toml".to_string()).unwrap();
+    /// system.await_shutdown(None);
+    /// ```
+    pub fn spawn_blocking<F, T>(
+        &self,
+        closure: F,
+    ) -> impl Future<Output = Result<T, StdError>> + Send + 'static
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let (sender, receiver) = oneshot::channel();
+        let spawned = self.spawn_task_on(
+            async move {
+                let result = catch_unwind(AssertUnwindSafe(closure)).map_err(Panic::from);
+                let _ = sender.send(result);
+            },
+            true,
+        );
+        async move {
+            spawned?;
+            Ok(receiver.await??)
+        }
+    }
+
+    /// Spawns the `future` like [`Context::spawn_task`], on the blocking threads if `blocking`
+    /// is set.
+    fn spawn_task_on<F>(&self, future: F, blocking: bool) -> Result<TaskHandle, SystemError>
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let (future, abort) = futures::future::abortable(future);
+        let state: TaskState = Mutex::new(Some(future.map(|_| ()).boxed()));
+        let aid = self.system.spawn().blocking(blocking).with(
+            state,
+            |state: TaskState, _: Context, _: Message| async move {
+                // The only message that the task receives is the start message.
+                let future = state.lock().unwrap().take();
+                if let Some(future) = future {
+                    future.await;
+                }
+                Ok(Status::stop(state))
+            },
+        )?;
+        Ok(TaskHandle { aid, abort })
+    }
+
+    /// Spawns the `future` like [`Context::spawn_task`] and sends its output as a message to
+    /// the `aid` once it completes, which is usually the [`Aid`] of the actor itself. The
+    /// message is a `Result<F::Output, Panic>`, which holds the [`Panic`] if the future panics.
+    /// For a future that can fail, the output is a `Result` so the error is delivered as well.
+    /// Note that the output must be serializable, so errors should be converted to a
+    /// serializable type such as a `String` first.
+    ///
+    /// # Examples
+    /// ```
+    /// use axiom::prelude::*;
+    /// use std::time::Duration;
+    ///
+    /// let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+    ///
+    /// let aid = system
+    ///     .spawn()
+    ///     .with((), |_: (), context: Context, message: Message| async move {
+    ///         if let Some(value) = message.content_as::<i32>() {
+    ///             let value = *value;
+    ///             let sleep = context.sleep(Duration::from_millis(10));
+    ///             let lookup = async move {
+    ///                 sleep.await;
+    ///                 value * 2
+    ///             };
+    ///             context.pipe_to(lookup, context.aid.clone())?;
+    ///         } else if let Some(result) = message.content_as::<Result<i32, Panic>>() {
+    ///             assert!(matches!(*result, Ok(22)));
+    ///             context.system.trigger_shutdown();
+    ///         }
+    ///         Ok(Status::done(()))
+    ///     })
+    ///     .unwrap();
+    ///
+    /// aid.send_new(11).unwrap();
+    /// system.await_shutdown(None);
+    /// ```
+    pub fn pipe_to<F>(&self, future: F, aid: Aid) -> Result<TaskHandle, SystemError>
+    where
+        F: Future + Send + 'static,
+        Result<F::Output, Panic>: 'static + ActorMessage,
+    {
+        self.spawn_task(async move {
+            let output = AssertUnwindSafe(future)
+                .catch_unwind()
+                .await
+                .map_err(Panic::from);
+            aid.send_new(output).unwrap_or_else(|error| {
+                warn!("Could not pipe the output of a task to {}: {}", aid, error)
+            });
+        })
+    }
+
+    /// Sets the receive timeout of the actor, replacing the one set with
+    /// [`ActorBuilder::receive_timeout`], or removes it if the timeout is `None`. The new timeout
+    /// is used from the next time that the actor runs out of messages. Note that passing a
+    /// timeout of zero will cause a panic.
+    pub fn set_receive_timeout(&self, timeout: Option<Duration>) {
+        assert!(timeout != Some(Duration::from_millis(0)));
+        *self.receive_timeout.lock().unwrap() = timeout;
+    }
 }
 
 impl std::fmt::Display for Context {
@@ -744,18 +2158,179 @@ where
 {
 }
 
-pub(crate) type HandlerFuture =
-    Pin<Box<dyn Future<Output = Result<Status, StdError>> + Send + 'static>>;
+/// Implemented by an actor type for each type of message `M` that it handles, as an
+/// alternative to matching on the type of each message in a single processor function.
+///
+/// The actor type also needs to implement [`Dispatch`] to route each message to the correct
+/// handler, which is typically done with the `Dispatch` derive macro available with the
+/// `derive` feature. The actor is then spawned with [`Dispatch::dispatch`] as its processor.
+///
+/// # Examples
+/// ```
+/// use axiom::prelude::*;
+/// use std::sync::Arc;
+///
+/// struct Counter {
+///     count: usize,
+/// }
+///
+/// impl Handler<usize> for Counter {
+///     async fn handle(mut self, _: Context, message: Arc<usize>) -> ActorResult<Self> {
+///         self.count += *message;
+///         Ok(Status::done(self))
+///     }
+/// }
+///
+/// impl Handler<bool> for Counter {
+///     async fn handle(mut self, _: Context, message: Arc<bool>) -> ActorResult<Self> {
+///         if *message {
+///             self.count = 0;
+///         }
+///         Ok(Status::done(self))
+///     }
+/// }
+///
+/// impl Dispatch for Counter {
+///     async fn dispatch(self, context: Context, message: Message) -> ActorResult<Self> {
+///         if let Some(msg) = message.content_as::<usize>() {
+///             self.handle(context, msg).await
+///         } else if let Some(msg) = message.content_as::<bool>() {
+///             self.handle(context, msg).await
+///         } else {
+///             self.unhandled(context, message)
+///         }
+///     }
+/// }
+///
+/// let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+/// let aid = system
+///     .spawn()
+///     .with(Counter { count: 0 }, Counter::dispatch)
+///     .unwrap();
+/// aid.send_new(11 as usize).unwrap();
+/// aid.send_new(true).unwrap();
+/// ```
+pub trait Handler<M: ActorMessage>: Sized + Send + Sync + 'static {
+    /// Handles a message of type `M`, returning the new state and the status just like a
+    /// processor function.
+    fn handle(
+        self,
+        context: Context,
+        message: Arc<M>,
+    ) -> impl Future<Output = ActorResult<Self>> + Send;
+}
 
-/// This is the internal type for the handler that will manage the state for the actor using the
+/// Implemented by an actor type to route each message to the [`Handler`] for its type. This is
+/// usually derived with `#[derive(Dispatch)]` and `#[handles(...)]` listing the message types
+/// that the actor has a [`Handler`] for.
+pub trait Dispatch: Sized + Send + Sync + 'static {
+    /// Routes the message to the [`Handler`] for its type and passes messages that none of the
+    /// handlers accept to [`Dispatch::unhandled`]. This is the processor of the actor.
+    fn dispatch(
+        self,
+        context: Context,
+        message: Message,
+    ) -> impl Future<Output = ActorResult<Self>> + Send;
+
+    /// Called with messages that none of the handlers of the actor accept. A [`SystemMsg`] is
+    /// accepted silently as every actor is sent them, whereas any other message is sent to the
+    /// dead letters with [`DeadLetterReason::Unhandled`].
+    fn unhandled(self, context: Context, message: Message) -> ActorResult<Self> {
+        if message.content_as::<SystemMsg>().is_none() {
+            debug!("{}: Unhandled message received.", context.aid);
+            let recipient = Some(context.aid.clone());
+            let reason = DeadLetterReason::Unhandled;
+            let system = &context.system;
+            system.dead_letter(message, context.aid.uuid(), recipient, reason);
+        }
+        Ok(Status::done(self))
+    }
+}
+
+pub(crate) type HandlerFuture =
+    Pin<Box<dyn Future<Output = Result<Status, StdError>> + Send + 'static>>;
+
+/// This is the internal type for the handler that will manage the state for the actor using the
 /// user-provided message processor.
-pub(crate) trait Handler:
+pub(crate) trait MessageHandler:
     (FnMut(Context, Message) -> HandlerFuture) + Send + Sync + 'static
 {
 }
 
-// Allows any static function or closure, to be used as a Handler.
-impl<F> Handler for F where F: (FnMut(Context, Message) -> HandlerFuture) + Send + Sync + 'static {}
+// Allows any static function or closure, to be used as a MessageHandler.
+impl<F> MessageHandler for F where
+    F: (FnMut(Context, Message) -> HandlerFuture) + Send + Sync + 'static
+{
+}
+
+/// The type-erased form of a lifecycle hook set with the [`ActorBuilder`].
+type Hook = Box<dyn FnOnce(Context) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync>;
+
+/// The lifecycle hooks of an actor, each of which is taken when it is run so that it can only
+/// be run once.
+#[derive(Default)]
+pub(crate) struct Hooks {
+    /// See [`ActorBuilder::on_started`].
+    started: Option<Hook>,
+    /// See [`ActorBuilder::on_stopping`].
+    stopping: Option<Hook>,
+    /// See [`ActorBuilder::on_stopped`].
+    stopped: Option<Hook>,
+}
+
+impl Hooks {
+    /// Wraps a hook function so it can be stored in the hooks.
+    fn hook<F, R>(hook: F) -> Option<Hook>
+    where
+        F: FnOnce(Context) -> R + Send + Sync + 'static,
+        R: Future<Output = ()> + Send + 'static,
+    {
+        Some(Box::new(move |context: Context| hook(context).boxed()))
+    }
+
+    /// Runs the hook if it is present, catching any panic so that a misbehaving hook can't
+    /// prevent the actor from stopping properly.
+    async fn run(hook: Option<Hook>, context: Context) {
+        if let Some(hook) = hook {
+            let aid = context.aid.clone();
+            let future = catch_unwind(AssertUnwindSafe(|| hook(context)));
+            let result = match future {
+                Ok(future) => AssertUnwindSafe(future).catch_unwind().await,
+                Err(panic) => Err(panic),
+            };
+            if let Err(panic) = result {
+                error!("[{}] lifecycle hook panicked: {}", aid, Panic::from(panic));
+            }
+        }
+    }
+}
+
+/// What happens to the messages sent to an actor while its mailbox is full, which is when its
+/// message channel holds [`ActorBuilder::channel_size`] messages, including the one that the
+/// actor is processing. Set with [`ActorBuilder::overflow`].
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub enum MailboxOverflow {
+    /// Blocks the sender until there's room in the mailbox, for up to
+    /// [`ActorSystemConfig::send_timeout`], after which sending fails with
+    /// [`AidError::SendTimedOut`]. [`Aid::send_async`] waits for room without blocking instead.
+    /// This is the default.
+    #[default]
+    Block,
+    /// Fails to send right away with [`AidError::MailboxFull`].
+    FailFast,
+    /// Drops the message being sent.
+    DropNewest,
+    /// Drops the oldest message in the mailbox that the actor hasn't taken or skipped yet, to
+    /// make room for the message being sent.
+    DropOldest,
+    /// Sends the message being sent to the dead letters with [`DeadLetterReason::MailboxFull`].
+    DeadLetter,
+    /// Never drops messages, buffering the ones that don't fit in the message channel for as long
+    /// as it takes the actor to get to them. Use this for actors that can't lose messages and
+    /// whose senders can't wait, at the cost of memory growing without bound if the actor can't
+    /// keep up.
+    Unbounded,
+}
 
 /// A builder that can be used to create and spawn an actor. To get a builder, the user would ask
 /// the actor system to create one using `system.spawn()` and then to spawn the actor by means of
@@ -768,6 +2343,40 @@ pub struct ActorBuilder {
     /// The size of the message channel for the actor which defaults to `None`; meaning the
     /// default for the actor system will be used for the message channel.
     pub channel_size: Option<u16>,
+    /// The parent of the actor, set when the builder was obtained with [`Context::spawn`].
+    pub(crate) parent: Option<Aid>,
+    /// The lifecycle hooks that will be run by the actor.
+    pub(crate) hooks: Hooks,
+    /// Whether the actor traps the stops of linked actors, which defaults to `false`.
+    pub trap_exits: bool,
+    /// How long the actor can go without receiving a message before it is sent a
+    /// [`SystemMsg::ReceiveTimeout`], which defaults to `None` meaning it is never sent.
+    pub receive_timeout: Option<Duration>,
+    /// Whether the actor is stopped rather than sent a [`SystemMsg::ReceiveTimeout`] when its
+    /// receive timeout elapses, which defaults to `false`.
+    pub stop_on_receive_timeout: bool,
+    /// Whether the actor runs on the blocking threads of the actor system, which defaults to
+    /// `false`.
+    pub blocking: bool,
+    /// Whether the actor runs on a thread of its own, which defaults to `false`.
+    pub pinned: bool,
+    /// Whether the actor is woken on the thread that it last ran on, which defaults to `None`
+    /// meaning [`ActorSystemConfig::sticky_scheduling`] is used.
+    pub sticky: Option<bool>,
+    /// The priority of the actor, which defaults to `Priority::Normal`.
+    pub priority: Priority,
+    /// What happens to the messages sent to the actor while its mailbox is full, which defaults
+    /// to `MailboxOverflow::Block`.
+    pub overflow: MailboxOverflow,
+    /// Whether the actor takes messages out of its mailbox in the order of their priority,
+    /// which defaults to `false`.
+    pub priority_mailbox: bool,
+    /// Assigns a priority to the messages sent to the actor, set with
+    /// [`ActorBuilder::classify`].
+    pub(crate) classifier: Option<Classifier>,
+    /// The number of messages that the actor can stash, which defaults to `None` meaning the
+    /// size of its message channel is used.
+    pub stash_capacity: Option<usize>,
 }
 
 impl ActorBuilder {
@@ -776,23 +2385,396 @@ impl ActorBuilder {
     /// `ActorSystem::spawn` for more information and examples.
     ///
     // FIXME Consider implementing `using` to spawn a stateless actor.
-    pub fn with<F, S, R>(self, state: S, processor: F) -> Result<Aid, SystemError>
+    pub fn with<F, S, R>(mut self, state: S, processor: F) -> Result<Aid, SystemError>
     where
         S: Send + Sync + 'static,
         R: Future<Output = ActorResult<S>> + Send + 'static,
         F: Processor<S, R> + 'static,
     {
-        let (actor, stream) = Actor::new(self.system.clone(), &self, state, processor);
+        if let Some(name) = &self.name {
+            if name.contains('/') {
+                return Err(SystemError::InvalidName(name.clone()));
+            }
+        }
+        let hooks = std::mem::take(&mut self.hooks);
+        let (actor, stream) = Actor::new(self.system.clone(), &self, state, processor, hooks);
         debug!("Actor created: {}", actor.context.aid.uuid());
         self.system.register_actor(actor, stream)
     }
 
-    /// Set the name of the actor to the given string.
+    /// Completes the spawning of the actor like [`ActorBuilder::with`] but returns a
+    /// [`TypedAid`] that only accepts messages of type `M`. The processor is still responsible
+    /// for handling the [`SystemMsg`]s that every actor receives. See [`TypedAid`] for examples.
+    pub fn with_typed<M, F, S, R>(self, state: S, processor: F) -> Result<TypedAid<M>, SystemError>
+    where
+        M: 'static + ActorMessage,
+        S: Send + Sync + 'static,
+        R: Future<Output = ActorResult<S>> + Send + 'static,
+        F: Processor<S, R> + 'static,
+    {
+        Ok(TypedAid {
+            aid: self.with(state, processor)?,
+            message: PhantomData,
+        })
+    }
+
+    /// Sets a hook that is run once when the actor starts, before it processes its first
+    /// message. If the actor is stopped before it gets to process a message the hook is still
+    /// run before the [`ActorBuilder::on_stopping`] hook so the two can always be paired.
+    ///
+    /// # Examples
+    /// ```
+    /// use axiom::prelude::*;
+    ///
+    /// let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+    ///
+    /// let aid = system
+    ///     .spawn()
+    ///     .on_started(|context: Context| async move {
+    ///         println!("{} started", context.aid);
+    ///     })
+    ///     .on_stopping(|context: Context| async move {
+    ///         println!("{} stopping", context.aid);
+    ///     })
+    ///     .on_stopped(|context: Context| async move {
+    ///         println!("{} stopped", context.aid);
+    ///     })
+    ///     .with((), |_: (), _: Context, _: Message| async move { Ok(Status::done(())) })
+    ///     .unwrap();
+    ///
+    /// system.trigger_and_await_shutdown(None);
+    /// ```
+    pub fn on_started<F, R>(mut self, hook: F) -> Self
+    where
+        F: FnOnce(Context) -> R + Send + Sync + 'static,
+        R: Future<Output = ()> + Send + 'static,
+    {
+        self.hooks.started = Hooks::hook(hook);
+        self
+    }
+
+    /// Sets a hook that is run once when the actor stops, however it was stopped. When the
+    /// actor stops itself, by returning [`Status::Stop`], an error or panicking, or when it is
+    /// sent a [`SystemMsg::Stop`], the hook is run before the actor is removed from the actor
+    /// system. When the actor is stopped with [`ActorSystem::stop_actor`], by its parent
+    /// stopping or by the actor system shutting down, the actor has already been removed when
+    /// the hook is run. Note that hooks run at shutdown are run after the threads of the actor
+    /// system have stopped, so they shouldn't wait on other actors.
+    pub fn on_stopping<F, R>(mut self, hook: F) -> Self
+    where
+        F: FnOnce(Context) -> R + Send + Sync + 'static,
+        R: Future<Output = ()> + Send + 'static,
+    {
+        self.hooks.stopping = Hooks::hook(hook);
+        self
+    }
+
+    /// Sets a hook that is run once after the actor has stopped, been removed from the actor
+    /// system and its monitors have been notified. See [`ActorBuilder::on_stopping`] for how
+    /// the hooks are run when stopping.
+    pub fn on_stopped<F, R>(mut self, hook: F) -> Self
+    where
+        F: FnOnce(Context) -> R + Send + Sync + 'static,
+        R: Future<Output = ()> + Send + 'static,
+    {
+        self.hooks.stopped = Hooks::hook(hook);
+        self
+    }
+
+    /// Set the name of the actor to the given string. Names may not contain a `/` as that is
+    /// used to separate the names of parents from the names of their children.
     pub fn name(mut self, name: impl Into<String>) -> Self {
         self.name = Some(name.into());
         self
     }
 
+    /// Sets whether the actor traps exits. An actor that traps exits isn't stopped when an actor
+    /// linked to it stops abnormally but is sent a [`SystemMsg::Stopped`] instead, for every
+    /// linked actor that stops. See [`ActorSystem::link`] for more information.
+    pub fn trap_exits(mut self, trap_exits: bool) -> Self {
+        self.trap_exits = trap_exits;
+        self
+    }
+
+    /// Sets how long the actor can go without receiving a message before it is sent a
+    /// [`SystemMsg::ReceiveTimeout`]. The timeout starts every time the actor runs out of
+    /// messages and is reset by the next message the actor receives, so the actor is sent the
+    /// message again for as long as it stays idle. The timeout can be changed by the actor with
+    /// [`Context::set_receive_timeout`]. Note that passing a timeout of zero will cause a panic.
+    ///
+    /// # Examples
+    /// ```
+    /// use axiom::prelude::*;
+    /// use std::time::Duration;
+    ///
+    /// let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+    ///
+    /// let aid = system
+    ///     .spawn()
+    ///     .receive_timeout(Duration::from_millis(10))
+    ///     .with((), |_: (), context: Context, message: Message| async move {
+    ///         if let Some(msg) = message.content_as::<SystemMsg>() {
+    ///             if let SystemMsg::ReceiveTimeout = &*msg {
+    ///                 println!("{} is idle", context.aid);
+    ///                 return Ok(Status::stop(()));
+    ///             }
+    ///         }
+    ///         Ok(Status::done(()))
+    ///     })
+    ///     .unwrap();
+    ///
+    /// system.trigger_and_await_shutdown(None);
+    /// ```
+    pub fn receive_timeout(mut self, timeout: Duration) -> Self {
+        assert!(timeout > Duration::from_millis(0));
+        self.receive_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets whether the actor is stopped when its receive timeout elapses, with the reason
+    /// [`StopReason::ReceiveTimeout`], instead of being sent a [`SystemMsg::ReceiveTimeout`].
+    /// This has no effect unless the actor has a receive timeout.
+    pub fn stop_on_receive_timeout(mut self, stop: bool) -> Self {
+        self.stop_on_receive_timeout = stop;
+        self
+    }
+
+    /// Sets whether the actor runs on the separate pool of blocking threads, sized with
+    /// [`ActorSystemConfig::blocking_pool_size`], rather than on the threads that run the other
+    /// actors. This is meant for actors that block while processing messages, for example to
+    /// do file I/O or call a synchronous client, so that they don't keep the other actors from
+    /// running. Blocking actors are not warned about for exceeding the
+    /// [`ActorSystemConfig::warn_threshold`].
+    ///
+    /// # Examples
+    /// ```
+    /// use axiom::prelude::*;
+    ///
+    /// let system = ActorSystem::create(ActorSystemConfig::default().blocking_pool_size(2));
+    ///
+    /// let aid = system
+    ///     .spawn()
+    ///     .blocking(true)
+    ///     .with((), |_: (), context: Context, message: Message| async move {
+    ///         if let Some(path) = message.content_as::<String>() {
+    ///             let exists = std::path::Path::new(&*path).exists();
+    ///             println!("{} exists: {}", path, exists);
+    ///             context.system.trigger_shutdown();
+    ///         }
+    ///         Ok(Status::done(()))
+    ///     })
+    ///     .unwrap();
+    ///
+    /// aid.send_new("Cargo.toml".to_string()).unwrap();
+    /// system.await_shutdown(None);
+    /// ```
+    pub fn blocking(mut self, blocking: bool) -> Self {
+        self.blocking = blocking;
+        self
+    }
+
+    /// Sets whether the actor runs on a thread of its own for its whole life, rather than on the
+    /// threads shared with the other actors. This is meant for actors that wrap resources that
+    /// must always be used from the same thread, such as some FFI libraries. The thread is named
+    /// `Actor-` followed by the name of the actor, or its uuid if it has no name, and ends when
+    /// the actor stops. Apart from that, pinned actors are the same as any other actor, so they
+    /// are monitored, linked and stopped on shutdown as usual. The hooks of the actor run on the
+    /// same thread as well. This takes precedence over [`ActorBuilder::blocking`].
+    ///
+    /// # Examples
+    /// ```
+    /// use axiom::prelude::*;
+    /// use std::thread;
+    ///
+    /// let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+    ///
+    /// let aid = system
+    ///     .spawn()
+    ///     .name("interpreter")
+    ///     .pinned(true)
+    ///     .with((), |_: (), context: Context, message: Message| async move {
+    ///         if let Some(_) = message.content_as::<String>() {
+    ///             assert_eq!(Some("Actor-interpreter"), thread::current().name());
+    ///             context.system.trigger_shutdown();
+    ///         }
+    ///         Ok(Status::done(()))
+    ///     })
+    ///     .unwrap();
+    ///
+    /// aid.send_new("print('hello')".to_string()).unwrap();
+    /// system.await_shutdown(None);
+    /// ```
+    pub fn pinned(mut self, pinned: bool) -> Self {
+        self.pinned = pinned;
+        self
+    }
+
+    /// Sets whether the actor is woken on the worker thread that it last ran on, unless that
+    /// thread is overloaded, rather than on the thread with the fewest awake actors. This
+    /// overrides [`ActorSystemConfig::sticky_scheduling`] for the actor, so that actors that
+    /// process a lot of messages can keep their data in the cache of the same CPU, or so that an
+    /// actor can opt out when the rest of the system is sticky.
+    ///
+    /// # Examples
+    /// ```
+    /// use axiom::prelude::*;
+    ///
+    /// let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+    ///
+    /// let aid = system
+    ///     .spawn()
+    ///     .sticky(true)
+    ///     .with(0 as usize, |count: usize, _: Context, _: Message| async move {
+    ///         Ok(Status::done(count + 1))
+    ///     })
+    ///     .unwrap();
+    ///
+    /// aid.send_new(11).unwrap();
+    /// system.trigger_and_await_shutdown(None);
+    /// ```
+    pub fn sticky(mut self, sticky: bool) -> Self {
+        self.sticky = Some(sticky);
+        self
+    }
+
+    /// Sets the priority of the actor, which the [`SchedulingPolicy`] of the actor system may use
+    /// to decide which woken actors run first. With [`Scheduling::Priority`], the worker threads
+    /// run the woken actors with a higher priority before the others, so that actors such as
+    /// ones handling control messages aren't held up by busy actors.
+    ///
+    /// # Examples
+    /// ```
+    /// use axiom::prelude::*;
+    ///
+    /// let config = ActorSystemConfig::default().scheduling(Scheduling::Priority);
+    /// let system = ActorSystem::create(config);
+    ///
+    /// let aid = system
+    ///     .spawn()
+    ///     .priority(Priority::High)
+    ///     .with(0 as usize, |count: usize, _: Context, _: Message| async move {
+    ///         Ok(Status::done(count + 1))
+    ///     })
+    ///     .unwrap();
+    ///
+    /// aid.send_new(11).unwrap();
+    /// system.trigger_and_await_shutdown(None);
+    /// ```
+    pub fn priority(mut self, priority: Priority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Sets what happens to the messages sent to the actor while its mailbox is full. Note that
+    /// system messages, such as [`SystemMsg::Stop`], are never lost to a full mailbox; they are
+    /// always sent as with `MailboxOverflow::Block`. See [`MailboxOverflow`] for the options.
+    ///
+    /// # Examples
+    /// ```
+    /// use axiom::prelude::*;
+    ///
+    /// let system = ActorSystem::create(ActorSystemConfig::default());
+    ///
+    /// let aid = system
+    ///     .spawn()
+    ///     .channel_size(8)
+    ///     .overflow(MailboxOverflow::DropOldest)
+    ///     .with(0 as usize, |count: usize, _: Context, _: Message| async move {
+    ///         Ok(Status::done(count + 1))
+    ///     })
+    ///     .unwrap();
+    ///
+    /// for i in 0..100 {
+    ///     aid.send_new(i).unwrap();
+    /// }
+    /// system.trigger_and_await_shutdown(None);
+    /// ```
+    pub fn overflow(mut self, overflow: MailboxOverflow) -> Self {
+        self.overflow = overflow;
+        self
+    }
+
+    /// Sets whether the actor takes the messages out of its mailbox in the order of their
+    /// priority, rather than in the order that they were sent. Messages are sent with
+    /// `MessagePriority::Normal` unless they are sent with another priority with
+    /// [`Message::with_priority`] or the actor assigns them one with [`ActorBuilder::classify`].
+    /// System messages, such as [`SystemMsg::Stop`], jump ahead of all of the other messages.
+    ///
+    /// The mailbox is made of a message channel of [`ActorBuilder::channel_size`] messages for the
+    /// system messages and one for each priority, and the [`MailboxOverflow`] of the actor applies
+    /// to each of them separately. Messages with the same priority are taken in the order they
+    /// were sent. Messages that the actor skips with [`Status::Skip`] stay where they are and are
+    /// taken again, in the order of their priority, once the actor returns [`Status::Reset`].
+    ///
+    /// # Examples
+    /// ```
+    /// use axiom::prelude::*;
+    ///
+    /// let system = ActorSystem::create(ActorSystemConfig::default());
+    ///
+    /// let aid = system
+    ///     .spawn()
+    ///     .priority_mailbox(true)
+    ///     .with((), |_: (), _: Context, message: Message| async move {
+    ///         if let Some(text) = message.content_as::<String>() {
+    ///             println!("{}", text);
+    ///         }
+    ///         Ok(Status::done(()))
+    ///     })
+    ///     .unwrap();
+    ///
+    /// aid.send_new("data".to_string()).unwrap();
+    /// aid.send(Message::new("health check".to_string()).with_priority(MessagePriority::High))
+    ///     .unwrap();
+    /// system.trigger_and_await_shutdown(None);
+    /// ```
+    pub fn priority_mailbox(mut self, priority_mailbox: bool) -> Self {
+        self.priority_mailbox = priority_mailbox;
+        self
+    }
+
+    /// Gives the actor a priority mailbox, as with [`ActorBuilder::priority_mailbox`], in which
+    /// the priority of every message that isn't a system message is assigned by the `classifier`
+    /// rather than taken from the message. The classifier is called by the senders, so it
+    /// should be quick.
+    ///
+    /// # Examples
+    /// ```
+    /// use axiom::prelude::*;
+    ///
+    /// let system = ActorSystem::create(ActorSystemConfig::default());
+    ///
+    /// let aid = system
+    ///     .spawn()
+    ///     .classify(|message: &Message| match message.content_as::<u32>() {
+    ///         Some(_) => MessagePriority::High,
+    ///         None => MessagePriority::Low,
+    ///     })
+    ///     .with((), |_: (), _: Context, _: Message| async move {
+    ///         Ok(Status::done(()))
+    ///     })
+    ///     .unwrap();
+    ///
+    /// aid.send_new("bulk".to_string()).unwrap();
+    /// aid.send_new(11_u32).unwrap();
+    /// system.trigger_and_await_shutdown(None);
+    /// ```
+    pub fn classify<F>(mut self, classifier: F) -> Self
+    where
+        F: Fn(&Message) -> MessagePriority + Send + Sync + 'static,
+    {
+        self.priority_mailbox = true;
+        self.classifier = Some(Arc::new(classifier));
+        self
+    }
+
+    /// Sets the number of messages that the actor can stash with [`Context::stash`] instead of
+    /// the size of its message channel.
+    pub fn stash_capacity(mut self, capacity: usize) -> Self {
+        self.stash_capacity = Some(capacity);
+        self
+    }
+
     /// Set the size of the channel to the given value instead of the default for the actor system
     /// that the actor is spawned on. Note that passing a value less than 1 will cause a panic and
     /// there would be little reason to do so anyway.
@@ -801,21 +2783,65 @@ impl ActorBuilder {
         self.channel_size = Some(size);
         self
     }
+
+    /// The name the actor will be registered with, which is the name scoped under the name of
+    /// the parent if the actor has a parent.
+    fn full_name(&self) -> Option<String> {
+        match (&self.parent, &self.name) {
+            (Some(parent), Some(name)) => Some(format!("{}/{}", parent.name_or_uuid(), name)),
+            (None, Some(name)) => Some(name.clone()),
+            (_, None) => None,
+        }
+    }
 }
 
+/// The lane recorded by an [`ActorStream`] for a message that was taken from the unstashed
+/// messages of the actor rather than from its mailbox.
+const UNSTASHED: usize = usize::MAX;
+
 pub(crate) struct ActorStream {
     /// The context data for the actor containing the [`Aid`] as well as other immutable data.
     pub context: Context,
-    /// Receiver for the actor's message channel.
-    receiver: SeccReceiver<Message>,
+    /// The receive sides of the lanes of the actor's mailbox, in the order that the actor takes
+    /// messages from them.
+    receivers: Vec<SeccReceiver<Message>>,
+    /// The index of the lane that the message being processed was taken from, or `UNSTASHED` if
+    /// it was taken from the unstashed messages.
+    lane: usize,
+    /// Whether the message being processed was taken from the overflow buffer of the lane
+    /// rather than its message channel.
+    buffered: bool,
     /// An async function processing a message sent to the actor, wrapped in a closure to
     /// erase the state type that the actor is managing. The inner state is Arc<Mutex>'d to
     /// ensure the Actor is synchronous in relation to itself.
-    handler: Box<dyn Handler>,
+    handler: Box<dyn MessageHandler>,
     /// The pending result of the current handler invocation.
     pending: Option<HandlerFuture>,
     /// Set to true when the stream receives SystemMsg::Stop
     stopping: bool,
+    /// The lifecycle hooks of the actor that have yet to be run.
+    hooks: Hooks,
+    /// Set when the actor has stopped itself, along with the reason that it stopped.
+    stop: Option<StopReason>,
+    /// The future running the stop hooks and removing the actor from the system once stopped.
+    finishing: Option<Pin<Box<dyn Future<Output = ()> + Send>>>,
+    /// Set to true when the actor has completely stopped and the stream should be dropped.
+    finished: bool,
+    /// Whether the actor is stopped when its receive timeout elapses.
+    stop_on_receive_timeout: bool,
+    /// The [`SystemMsg::ReceiveTimeout`] scheduled when the actor last ran out of messages and
+    /// when that was, if the actor hasn't received a message since.
+    idle: Option<(ScheduleHandle, Instant)>,
+    /// Whether the actor runs on the blocking threads. See [`ActorBuilder::blocking`].
+    pub(crate) blocking: bool,
+    /// Whether the actor runs on a thread of its own. See [`ActorBuilder::pinned`].
+    pub(crate) pinned: bool,
+    /// Whether the actor is woken on the thread it last ran on. See [`ActorBuilder::sticky`].
+    pub(crate) sticky: bool,
+    /// The priority of the actor. See [`ActorBuilder::priority`].
+    pub(crate) priority: Priority,
+    /// The send side of the actor's mailbox.
+    mailbox: Arc<Mailbox>,
 }
 
 /// The implementation of the actor in the system. Please see overview and library documentation
@@ -823,6 +2849,11 @@ pub(crate) struct ActorStream {
 pub(crate) struct Actor {
     /// The context data for the actor containing the `aid` as well as other immutable data.
     pub context: Context,
+    /// The actor that spawned this actor with [`Context::spawn`], if any.
+    pub parent: Option<Aid>,
+    /// Whether the actor receives the stops of linked actors as messages. See
+    /// [`ActorBuilder::trap_exits`].
+    pub trap_exits: bool,
 }
 
 /// This is exclusively used in contexts we can be more than confident are safe.
@@ -849,29 +2880,31 @@ impl Actor {
         builder: &ActorBuilder,
         state: S,
         mut processor: F,
+        hooks: Hooks,
     ) -> (Arc<Actor>, ActorStream)
     where
         S: Send + Sync + 'static,
         R: Future<Output = ActorResult<S>> + Send + 'static,
         F: Processor<S, R> + 'static,
     {
-        let (sender, receiver) = secc::create::<Message>(
-            builder
-                .channel_size
-                .unwrap_or(system.config().message_channel_size),
-            Duration::from_millis(10),
-        );
+        let channel_size = builder
+            .channel_size
+            .unwrap_or(system.config().message_channel_size);
+        let (mailbox, receivers) = Mailbox::new(builder, channel_size);
+        let stash_capacity = builder.stash_capacity.unwrap_or(channel_size as usize);
 
-        // The sender will be put inside the actor id.
+        // The mailbox will be put inside the actor id.
+        let mailbox = Arc::new(mailbox);
         let aid = Aid {
             data: Arc::new(AidData {
                 uuid: Uuid::new_v4(),
                 system_uuid: system.uuid(),
-                name: builder.name.clone(),
+                name: builder.full_name(),
                 sender: ActorSender::Local {
                     system: system.clone(),
                     stopped: AtomicBool::new(false),
-                    sender,
+                    mailbox: mailbox.clone(),
+                    overflow: builder.overflow,
                 },
             }),
         };
@@ -905,23 +2938,47 @@ impl Actor {
                     status
                 })
             }
-                .boxed()
+            .boxed()
         });
 
         // This is the receiving side of the actor which holds the processor wrapped in the
         // handler type.
-        let context = Context { aid, system };
+        let context = Context {
+            aid,
+            system,
+            reply_to: None,
+            receive_timeout: Arc::new(Mutex::new(builder.receive_timeout)),
+            stash: Arc::new(Mutex::new(Stash::new(stash_capacity))),
+        };
 
         let actor = Actor {
             context: context.clone(),
+            parent: builder.parent.clone(),
+            trap_exits: builder.trap_exits,
         };
 
+        let sticky = builder
+            .sticky
+            .unwrap_or(context.system.config().sticky_scheduling);
         let stream = ActorStream {
             context,
-            receiver,
+            receivers,
+            lane: 0,
+            buffered: false,
             handler,
             pending: None,
             stopping: false,
+            hooks,
+            stop: None,
+            finishing: None,
+            finished: false,
+            stop_on_receive_timeout: builder.stop_on_receive_timeout,
+            idle: None,
+            blocking: builder.blocking,
+            pinned: builder.pinned,
+            sticky,
+            priority: builder.priority,
+            mailbox,
         };
 
         (Arc::new(actor), stream)
@@ -930,56 +2987,120 @@ impl Actor {
 
 impl ActorStream {
     /// This takes the result and executes the subsequent steps in respect to the result. Namely,
-    /// handling the Actor's message channel and informing the ActorSystem of errors. Returns
-    /// whether the Actor is stopping or not.
-    pub(crate) fn handle_result(&self, result: Result<Status, StdError>) -> bool {
-        let mut stopping = false;
-
+    /// handling the Actor's message channel and recording whether the Actor stopped itself so
+    /// that the stream will stop the Actor the next time that it is polled.
+    pub(crate) fn handle_result(&mut self, result: Result<Status, StdError>) {
+        let skipped = matches!(result, Ok(Status::Skip));
         match result {
             Ok(Status::Done) => {
                 trace!(
                     "Actor {} finished processing a message",
                     self.context.aid.uuid()
                 );
-                self.receiver.pop().unwrap()
+                self.pop()
             }
             Ok(Status::Skip) => {
                 trace!(
                     "Actor {} skipped processing a message",
                     self.context.aid.uuid()
                 );
-                self.receiver.skip().unwrap()
+                self.skip()
             }
             Ok(Status::Reset) => {
                 trace!(
                     "Actor {} finished processing a message and reset the cursor",
                     self.context.aid.uuid()
                 );
-                self.receiver.pop().unwrap();
-                self.receiver.reset_skip().unwrap();
+                self.pop();
+                // The messages skipped in any of the lanes can be taken again.
+                for receiver in self.receivers.iter() {
+                    receiver.reset_skip().unwrap();
+                }
+                self.mailbox.reset_skip();
             }
             Ok(Status::Stop) => {
                 debug!("Actor \"{}\" stopping", self.context.aid.name_or_uuid());
-                self.receiver.pop().unwrap();
-                self.context
-                    .system
-                    .internal_stop_actor(&self.context.aid, None);
-                stopping = true;
+                self.pop();
+                self.stop = Some(StopReason::Normal);
             }
             Err(e) => {
-                self.receiver.pop().unwrap();
+                self.pop();
                 error!(
                     "[{}] returned an error when processing: {}",
                     self.context.aid, &e
                 );
-                self.context
-                    .system
-                    .internal_stop_actor(&self.context.aid, e);
-                stopping = true;
+                self.stop = Some(StopReason::from(e));
+            }
+        }
+        // There's room for the next sender waiting, unless the message was skipped or unstashed.
+        if !skipped && self.lane != UNSTASHED {
+            self.mailbox.wake_one(self.lane);
+        }
+    }
+
+    /// Removes the message that the actor processed from the lane of the mailbox that it was
+    /// taken from, or from the unstashed messages.
+    fn pop(&self) {
+        match self.lane {
+            UNSTASHED => drop(self.context.stash.lock().unwrap().unstashed.pop_front()),
+            lane if self.buffered => self.mailbox.buffer(lane).unwrap().pop(),
+            lane => self.receivers[lane].pop().unwrap(),
+        }
+    }
+
+    /// Leaves the message that the actor skipped in the lane of the mailbox that it was taken
+    /// from, or stashes it again if it was unstashed.
+    fn skip(&self) {
+        match self.lane {
+            UNSTASHED => {
+                let mut stash = self.context.stash.lock().unwrap();
+                if let Some(message) = stash.unstashed.pop_front() {
+                    stash.stashed.push_back(message);
+                }
             }
+            lane if self.buffered => self.mailbox.buffer(lane).unwrap().skip(),
+            lane => self.receivers[lane].skip().unwrap(),
         }
+    }
 
-        stopping
+    /// Peeks at the next message that the actor has to take, recording where it's taken from.
+    /// This is the first message of the first lane of the mailbox that has a message to take,
+    /// with the unstashed messages coming right after the system messages of a priority mailbox
+    /// and before any other messages. Once the actor has skipped everything in the message
+    /// channel of a lane, the messages in the overflow buffer of the lane come next.
+    fn peek(&mut self) -> Result<Message, SeccErrors<Message>> {
+        let system_lanes = if self.receivers.len() > 1 { 1 } else { 0 };
+        let lanes = (0..system_lanes)
+            .chain(iter::once(UNSTASHED))
+            .chain(system_lanes..self.receivers.len());
+        for lane in lanes {
+            let mut buffered = false;
+            let result = match lane {
+                UNSTASHED => match self.context.stash.lock().unwrap().unstashed.front() {
+                    Some(message) => Ok(message.clone()),
+                    None => Err(SeccErrors::Empty),
+                },
+                lane => match self.receivers[lane].peek() {
+                    Err(SeccErrors::Empty) => {
+                        match self.mailbox.buffer(lane).and_then(OverflowBuffer::peek) {
+                            Some(message) => {
+                                buffered = true;
+                                Ok(message)
+                            }
+                            None => Err(SeccErrors::Empty),
+                        }
+                    }
+                    result => result,
+                },
+            };
+            if let Err(SeccErrors::Empty) = result {
+                continue;
+            }
+            self.lane = lane;
+            self.buffered = buffered;
+            return result;
+        }
+        Err(SeccErrors::Empty)
     }
 
     fn overwrite_on_stop(&self, result: Result<Status, StdError>) -> Result<Status, StdError> {
@@ -988,6 +3109,72 @@ impl ActorStream {
             false => result,
         }
     }
+
+    /// Determines if the actor has completely stopped, having run all of its hooks.
+    pub(crate) fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Stops the actor because the actor system is shutting down, abandoning the message that
+    /// the actor is processing, if any. The stream must then be polled to completion to run the
+    /// stop hooks.
+    pub(crate) fn shutdown(&mut self) {
+        drop(self.pending.take());
+        if self.stop.is_none() {
+            self.stop = Some(StopReason::Shutdown);
+        }
+    }
+
+    /// Schedules a [`SystemMsg::ReceiveTimeout`] for the actor if it has a receive timeout and
+    /// doesn't have one scheduled already. This is called when the actor runs out of messages
+    /// and is returned to the sleeping actors of the executor.
+    pub(crate) fn sleep(&mut self) {
+        if self.idle.is_some() || self.stop.is_some() {
+            return;
+        }
+        if let Some(timeout) = self.context.receive_timeout() {
+            let handle = self.context.system.send_after(
+                Message::new(SystemMsg::ReceiveTimeout),
+                self.context.aid.clone(),
+                timeout,
+            );
+            self.idle = Some((handle, Instant::now()));
+        }
+    }
+
+    /// Determines if the actor has been idle for its whole receive timeout. A
+    /// [`SystemMsg::ReceiveTimeout`] that arrives when this isn't the case was scheduled before
+    /// the actor received its last message and is dropped.
+    fn is_timed_out(&self) -> bool {
+        match (&self.idle, self.context.receive_timeout()) {
+            (Some((_, since)), Some(timeout)) => since.elapsed() >= timeout,
+            _ => false,
+        }
+    }
+
+    /// Creates the future that runs the stop hooks of the actor and removes the actor from the
+    /// actor system in between them. Removing the actor from the system does nothing if it was
+    /// already removed.
+    fn finish(&mut self) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        if let Some((handle, _)) = self.idle.take() {
+            handle.cancel();
+        }
+        let reason = self.stop.take().unwrap_or(StopReason::Killed);
+        let context = self.context.clone();
+        let started = self.hooks.started.take();
+        let stopping = self.hooks.stopping.take();
+        let stopped = self.hooks.stopped.take();
+        let mailbox = self.mailbox.clone();
+        async move {
+            Hooks::run(started, context.clone()).await;
+            Hooks::run(stopping, context.clone()).await;
+            context.system.internal_stop_actor(&context.aid, reason);
+            // The waiting senders fail now that the actor is stopped.
+            mailbox.wake_all();
+            Hooks::run(stopped, context).await;
+        }
+        .boxed()
+    }
 }
 
 /// The meat of the Actor's handling
@@ -999,6 +3186,19 @@ impl Stream for ActorStream {
         cx: &mut std::task::Context<'_>,
     ) -> Poll<Option<Self::Item>> {
         trace!("Actor {} is being polled", self.context.aid.name_or_uuid());
+        // If the actor has stopped we only poll the hooks that finish it.
+        if let Some(finishing) = self.finishing.as_mut() {
+            return match finishing.as_mut().poll(cx) {
+                Poll::Ready(()) => {
+                    debug!("Actor {} finished", self.context.aid.name_or_uuid());
+                    self.finishing = None;
+                    self.finished = true;
+                    Poll::Ready(None)
+                }
+                Poll::Pending => Poll::Pending,
+            };
+        }
+
         // If we have a pending future, that's what we poll.
         if let Some(pending) = self.pending.as_mut() {
             // Poll, ensure we respect stopping condition.
@@ -1014,26 +3214,57 @@ impl Stream for ActorStream {
             }
 
             poll
+        } else if self.finished {
+            Poll::Ready(None)
+        } else if self.stop.is_some() || self.context.aid.is_stopped() {
+            // The actor stopped itself or was stopped by the system so we finish it.
+            self.finishing = Some(self.finish());
+            self.poll_next(cx)
         } else {
-            // Are we stopped? If so, we should not have been polled, panic. This is only acceptable
-            // because it means a bug in the Executor or Reactor.
-            if self.stopping {
-                panic!("Stopped ActorStream was polled after stopping. Please open a bug report.")
-            }
-            // Else, we go for another.
-            match self.receiver.peek() {
+            // Else, we go for another, after moving any overflowing messages into the mailbox.
+            self.mailbox.refill(&self.receivers);
+            match self.peek() {
                 Ok(msg) => {
-                    // We're stopping after this future, mark as such
                     if let Some(m) = msg.content_as::<SystemMsg>() {
-                        if let SystemMsg::Stop = *m {
-                            trace!("Actor {} received stop message", self.context.aid.uuid());
-                            self.stopping = true;
+                        match *m {
+                            // We're stopping after this future, mark as such
+                            SystemMsg::Stop => {
+                                trace!("Actor {} received stop message", self.context.aid.uuid());
+                                self.stopping = true;
+                            }
+                            // Stale timeouts are dropped and the actor is stopped instead of
+                            // processing the timeout if configured to do so.
+                            SystemMsg::ReceiveTimeout => {
+                                if !self.is_timed_out() {
+                                    trace!("Actor {} dropping stale timeout", self.context.aid);
+                                    return Poll::Ready(Some(Ok(Status::Done)));
+                                }
+                                if self.stop_on_receive_timeout {
+                                    debug!("Actor {} timed out", self.context.aid.name_or_uuid());
+                                    self.stop = Some(StopReason::ReceiveTimeout);
+                                    return Poll::Ready(Some(Ok(Status::Done)));
+                                }
+                            }
+                            _ => (),
                         }
                     }
+                    // Receiving a message resets the receive timeout.
+                    if let Some((handle, _)) = self.idle.take() {
+                        handle.cancel();
+                    }
 
-                    // Get the next future
-                    let ctx = self.context.clone();
+                    // Get the next future, running the started hook first if we haven't yet.
+                    let mut ctx = self.context.clone();
+                    ctx.reply_to = msg.reply_to();
                     let mut future = (&mut self.handler)(ctx, msg.clone());
+                    if let Some(started) = self.hooks.started.take() {
+                        let context = self.context.clone();
+                        future = async move {
+                            Hooks::run(Some(started), context).await;
+                            future.await
+                        }
+                        .boxed();
+                    }
                     // Just. give it a ~~wave~~ poll!!
                     match future.as_mut().poll(cx) {
                         Poll::Ready(r) => Poll::Ready(Some(self.overwrite_on_stop(r))),
@@ -1069,8 +3300,8 @@ mod tests {
     use super::*;
     use crate::tests::*;
     use log::*;
+    use std::sync::atomic::AtomicUsize;
     use std::thread;
-    use std::time::Instant;
 
     /// This is identical to the documentation but here so that its formatted by rust and we can
     /// copy paste this into the docs. It's also easier to debug here.
@@ -1288,22 +3519,427 @@ mod tests {
         }
     }
 
-    /// Tests that an actor that returns stop is actually stopped by the system.
+    /// Tests that `send_async` waits for room in the channel of a busy actor without holding up
+    /// the only thread that the actor and the sender run on, and that the messages arrive in
+    /// order.
     #[test]
-    fn test_actor_returns_stop() {
+    fn test_send_async() {
         init_test_log();
-        let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
-        let tracker = AssertCollect::new();
-        let t = tracker.clone();
 
-        let aid = system
+        let config = ActorSystemConfig::default()
+            .thread_pool_size(1)
+            .blocking_pool_size(0);
+        let system = ActorSystem::create(config);
+        let consumer = system
             .spawn()
-            .with(t, |t: AssertCollect, _: Context, message: Message| {
-                async move {
-                    if let Some(_msg) = message.content_as::<i32>() {
-                        Ok(Status::stop(t))
-                    } else if let Some(msg) = message.content_as::<SystemMsg>() {
-                        match &*msg {
+            .channel_size(2)
+            .with(0, |next: u32, _: Context, msg: Message| async move {
+                if let Some(value) = msg.content_as::<u32>() {
+                    assert_eq!(next, *value);
+                    sleep(1);
+                    return Ok(Status::done(next + 1));
+                }
+                Ok(Status::done(next))
+            })
+            .unwrap();
+        let producer = system
+            .spawn()
+            .with(
+                consumer.clone(),
+                |consumer: Aid, _: Context, msg: Message| async move {
+                    if msg.content_as::<SystemMsg>().is_none() {
+                        for i in 0..20_u32 {
+                            consumer.send_async(Message::new(i)).await?;
+                        }
+                    }
+                    Ok(Status::done(consumer))
+                },
+            )
+            .unwrap();
+        producer.send_new(()).unwrap();
+        await_received(&producer, 2, 1000).unwrap();
+        await_received(&consumer, 21, 1000).unwrap();
+        assert!(system.is_actor_alive(&producer));
+        assert!(system.is_actor_alive(&consumer));
+
+        system.trigger_and_await_shutdown(None);
+    }
+
+    /// Tests that a sender waiting with `send_async` gets an error once the actor stops.
+    #[test]
+    fn test_send_async_to_stopped() {
+        init_test_log();
+
+        let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+        // Skipping the messages keeps them in the channel, so it stays full.
+        let consumer = system
+            .spawn()
+            .channel_size(1)
+            .with((), |_: (), _: Context, msg: Message| async move {
+                match msg.content_as::<u32>() {
+                    Some(_) => Ok(Status::skip(())),
+                    None => Ok(Status::done(())),
+                }
+            })
+            .unwrap();
+        let result = Arc::new(Mutex::new(None));
+        let producer_result = result.clone();
+        let producer = system
+            .spawn()
+            .with(
+                consumer.clone(),
+                move |consumer: Aid, _: Context, msg: Message| {
+                    let result = producer_result.clone();
+                    async move {
+                        if msg.content_as::<SystemMsg>().is_none() {
+                            consumer.send_async(Message::new(1_u32)).await?;
+                            let sent = consumer.send_async(Message::new(2_u32)).await;
+                            *result.lock().unwrap() = Some(sent);
+                        }
+                        Ok(Status::done(consumer))
+                    }
+                },
+            )
+            .unwrap();
+        await_received(&consumer, 1, 1000).unwrap();
+        producer.send_new(()).unwrap();
+        // The first message is skipped, so it's never received.
+        while consumer.sent().unwrap() < 2 {
+            sleep(1);
+        }
+        sleep(10);
+        assert_eq!(None, *result.lock().unwrap());
+
+        system.stop_actor(&consumer);
+        await_received(&producer, 2, 1000).unwrap();
+        assert_eq!(
+            Some(Err(AidError::ActorAlreadyStopped)),
+            *result.lock().unwrap()
+        );
+
+        system.trigger_and_await_shutdown(None);
+    }
+
+    /// Spawns an actor with the `builder` that records the numbers that it's sent, and holds it
+    /// up on the number 0 until the returned flag is set so that messages pile up in its mailbox.
+    fn spawn_held(builder: ActorBuilder) -> (Aid, Arc<AtomicBool>, Arc<Mutex<Vec<u32>>>) {
+        let released = Arc::new(AtomicBool::new(false));
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let (actor_released, actor_received) = (released.clone(), received.clone());
+        let aid = builder
+            .with((), move |_: (), _: Context, msg: Message| {
+                let released = actor_released.clone();
+                let received = actor_received.clone();
+                async move {
+                    if let Some(value) = msg.content_as::<u32>() {
+                        received.lock().unwrap().push(*value);
+                        while !released.load(Ordering::SeqCst) {
+                            sleep(1);
+                        }
+                    }
+                    Ok(Status::done(()))
+                }
+            })
+            .unwrap();
+        await_received(&aid, 1, 1000).unwrap();
+        aid.send_new(0_u32).unwrap();
+        while received.lock().unwrap().is_empty() {
+            sleep(1);
+        }
+        (aid, released, received)
+    }
+
+    /// Waits for the actor spawned with [`spawn_held`] to record the `expected` numbers.
+    fn await_recorded(received: &Mutex<Vec<u32>>, expected: &[u32]) {
+        let start = Instant::now();
+        while received.lock().unwrap().len() < expected.len() {
+            let elapsed = start.elapsed();
+            assert!(elapsed < Duration::from_millis(1000), "{:?}", expected);
+            sleep(1);
+        }
+        sleep(10);
+        assert_eq!(expected, &received.lock().unwrap()[..]);
+    }
+
+    /// Tests what happens to the messages sent to an actor with a full mailbox for each of the
+    /// overflow policies.
+    #[test]
+    fn test_mailbox_overflow() {
+        init_test_log();
+
+        let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+        let cases = vec![
+            (MailboxOverflow::FailFast, vec![0, 1]),
+            (MailboxOverflow::DropNewest, vec![0, 1]),
+            (MailboxOverflow::DeadLetter, vec![0, 1]),
+            (MailboxOverflow::DropOldest, vec![0, 3, 4]),
+            (MailboxOverflow::Unbounded, vec![0, 1, 2, 3, 4]),
+        ];
+        for (overflow, expected) in cases {
+            let builder = system.spawn().channel_size(2).overflow(overflow);
+            let (aid, released, received) = spawn_held(builder);
+            // The mailbox is full with the message being processed and the next one.
+            aid.send_new(1_u32).unwrap();
+            for i in 2..5_u32 {
+                let result = aid.send_new(i);
+                match overflow {
+                    MailboxOverflow::FailFast => {
+                        assert_eq!(Err(AidError::MailboxFull(aid.clone())), result)
+                    }
+                    _ => assert_eq!(Ok(()), result),
+                }
+            }
+            released.store(true, Ordering::SeqCst);
+            await_recorded(&received, &expected);
+        }
+        let reason = DeadLetterReason::MailboxFull;
+        assert_eq!(3, system.dead_letter_count(reason));
+
+        system.trigger_and_await_shutdown(None);
+    }
+
+    /// Tests that system messages sent to an actor with a `DropOldest` mailbox wait for room in
+    /// a full mailbox and aren't dropped to make room for newer messages.
+    #[test]
+    fn test_drop_oldest_system_messages() {
+        init_test_log();
+
+        let config = ActorSystemConfig::default()
+            .thread_pool_size(2)
+            .send_timeout(Duration::from_millis(1000));
+        let system = ActorSystem::create(config);
+        let builder = system
+            .spawn()
+            .channel_size(3)
+            .overflow(MailboxOverflow::DropOldest);
+        let (aid, released, received) = spawn_held(builder);
+        aid.send_new(SystemMsg::Stop).unwrap();
+        // These push the stop and the oldest of them out of the channel.
+        for i in 1..6_u32 {
+            aid.send_new(i).unwrap();
+        }
+        released.store(true, Ordering::SeqCst);
+        await_recorded(&received, &[0]);
+        assert!(!system.is_actor_alive(&aid));
+
+        // A stop sent while the mailbox is full is taken once there's room for it, which may be
+        // before the buffered messages that were sent ahead of it.
+        let builder = system
+            .spawn()
+            .channel_size(3)
+            .overflow(MailboxOverflow::DropOldest);
+        let (aid, released, _) = spawn_held(builder);
+        for i in 1..6_u32 {
+            aid.send_new(i).unwrap();
+        }
+        let release = thread::spawn(move || {
+            sleep(10);
+            released.store(true, Ordering::SeqCst);
+        });
+        aid.send_new(SystemMsg::Stop).unwrap();
+        release.join().unwrap();
+        let start = Instant::now();
+        while system.is_actor_alive(&aid) {
+            assert!(start.elapsed() < Duration::from_millis(1000));
+            sleep(1);
+        }
+
+        system.trigger_and_await_shutdown(None);
+    }
+
+    /// Tests that an actor with an `Unbounded` mailbox takes the buffered messages once it has
+    /// skipped all of the messages in its message channel.
+    #[test]
+    fn test_unbounded_skip() {
+        init_test_log();
+
+        let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let actor_received = received.clone();
+        let aid = system
+            .spawn()
+            .channel_size(2)
+            .overflow(MailboxOverflow::Unbounded)
+            .with(false, move |ready: bool, _: Context, msg: Message| {
+                let received = actor_received.clone();
+                async move {
+                    if let Some(value) = msg.content_as::<u32>() {
+                        if !ready {
+                            return Ok(Status::skip(ready));
+                        }
+                        received.lock().unwrap().push(*value);
+                    } else if msg.content_as::<bool>().is_some() {
+                        return Ok(Status::reset(true));
+                    }
+                    Ok(Status::done(ready))
+                }
+            })
+            .unwrap();
+        await_received(&aid, 1, 1000).unwrap();
+        // The channel fills up with skipped messages so the rest are buffered.
+        for i in 1..4_u32 {
+            aid.send_new(i).unwrap();
+        }
+        aid.send_new(true).unwrap();
+        await_recorded(&received, &[1, 2, 3]);
+        assert_eq!(aid.sent().unwrap(), aid.received().unwrap());
+
+        system.trigger_and_await_shutdown(None);
+    }
+
+    /// Tests that an actor with a priority mailbox takes the messages with a higher priority
+    /// first, whether they are sent with the priority or assigned it by a classifier, and the
+    /// system messages before all of them.
+    #[test]
+    fn test_priority_mailbox() {
+        init_test_log();
+
+        let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+        let (aid, released, received) = spawn_held(system.spawn().priority_mailbox(true));
+        let priorities = [
+            MessagePriority::Low,
+            MessagePriority::Normal,
+            MessagePriority::High,
+        ];
+        for (i, priority) in (1..6_u32).zip(priorities.iter().cycle()) {
+            aid.send(Message::new(i).with_priority(*priority)).unwrap();
+        }
+        released.store(true, Ordering::SeqCst);
+        await_recorded(&received, &[0, 3, 2, 5, 1, 4]);
+
+        let builder = system.spawn().classify(|msg: &Message| {
+            match msg.content_as::<u32>().map(|value| *value % 2) {
+                Some(0) => MessagePriority::High,
+                _ => MessagePriority::Low,
+            }
+        });
+        let (aid, released, received) = spawn_held(builder);
+        for i in 1..5_u32 {
+            aid.send(Message::new(i).with_priority(MessagePriority::High))
+                .unwrap();
+        }
+        released.store(true, Ordering::SeqCst);
+        await_recorded(&received, &[0, 2, 4, 1, 3]);
+
+        // The stop is taken before the messages sent ahead of it.
+        let (aid, released, received) = spawn_held(system.spawn().priority_mailbox(true));
+        aid.send_new(1_u32).unwrap();
+        aid.send_new(SystemMsg::Stop).unwrap();
+        released.store(true, Ordering::SeqCst);
+        await_recorded(&received, &[0]);
+        assert!(!system.is_actor_alive(&aid));
+
+        system.trigger_and_await_shutdown(None);
+    }
+
+    /// Tests that the messages an actor with a priority mailbox skips are kept in their lanes
+    /// and taken in the order of their priority once the actor resets the cursor.
+    #[test]
+    fn test_priority_mailbox_skip() {
+        init_test_log();
+
+        let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let actor_received = received.clone();
+        let aid = system
+            .spawn()
+            .priority_mailbox(true)
+            .with(false, move |ready: bool, _: Context, msg: Message| {
+                let received = actor_received.clone();
+                async move {
+                    if let Some(value) = msg.content_as::<u32>() {
+                        if !ready {
+                            return Ok(Status::skip(ready));
+                        }
+                        received.lock().unwrap().push(*value);
+                    } else if msg.content_as::<bool>().is_some() {
+                        return Ok(Status::reset(true));
+                    }
+                    Ok(Status::done(ready))
+                }
+            })
+            .unwrap();
+        aid.send(Message::new(1_u32).with_priority(MessagePriority::Low))
+            .unwrap();
+        aid.send(Message::new(2_u32).with_priority(MessagePriority::High))
+            .unwrap();
+        aid.send_new(3_u32).unwrap();
+        await_received(&aid, 1, 1000).unwrap();
+        sleep(10);
+        assert!(received.lock().unwrap().is_empty());
+
+        aid.send(Message::new(true).with_priority(MessagePriority::Low))
+            .unwrap();
+        await_recorded(&received, &[2, 3, 1]);
+
+        system.trigger_and_await_shutdown(None);
+    }
+
+    /// Tests that stashed messages are moved out of the mailbox, so that they don't fill it,
+    /// that stashing fails once the stash is full and that unstashed messages are taken in the
+    /// order that they were stashed before the rest of the mailbox.
+    #[test]
+    fn test_stash() {
+        init_test_log();
+
+        let config = ActorSystemConfig::default()
+            .thread_pool_size(2)
+            .send_timeout(Duration::from_millis(1000));
+        let system = ActorSystem::create(config);
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let actor_received = received.clone();
+        let aid = system
+            .spawn()
+            .channel_size(1)
+            .stash_capacity(2)
+            .with(false, move |open: bool, context: Context, msg: Message| {
+                let received = actor_received.clone();
+                async move {
+                    if let Some(value) = msg.content_as::<u32>() {
+                        if open {
+                            received.lock().unwrap().push(*value);
+                        } else if let Err(e) = context.stash(msg) {
+                            assert_eq!(StashError::Full, e);
+                            assert_eq!(2, context.stashed());
+                        }
+                    } else if msg.content_as::<bool>().is_some() {
+                        context.unstash_all();
+                        assert_eq!(0, context.stashed());
+                        return Ok(Status::done(true));
+                    }
+                    Ok(Status::done(open))
+                }
+            })
+            .unwrap();
+        for i in 1..4_u32 {
+            aid.send_new(i).unwrap();
+        }
+        aid.send_new(true).unwrap();
+        aid.send_new(4_u32).unwrap();
+        // The unstashed messages aren't received again, but they are processed before the last.
+        await_received(&aid, 6, 1000).unwrap();
+        assert!(system.is_actor_alive(&aid));
+        assert_eq!(vec![1, 2, 4], *received.lock().unwrap());
+
+        system.trigger_and_await_shutdown(None);
+    }
+
+    /// Tests that an actor that returns stop is actually stopped by the system.
+    #[test]
+    fn test_actor_returns_stop() {
+        init_test_log();
+        let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+        let tracker = AssertCollect::new();
+        let t = tracker.clone();
+
+        let aid = system
+            .spawn()
+            .with(t, |t: AssertCollect, _: Context, message: Message| {
+                async move {
+                    if let Some(_msg) = message.content_as::<i32>() {
+                        Ok(Status::stop(t))
+                    } else if let Some(msg) = message.content_as::<SystemMsg>() {
+                        match &*msg {
                             SystemMsg::Start => Ok(Status::done(t)),
                             m => t.panic(format!("unexpected message: {:?}", m)),
                         }
@@ -1379,4 +4015,466 @@ mod tests {
         system.trigger_and_await_shutdown(None);
         tracker.collect();
     }
+
+    /// A processor that replies to `i32` messages with double the value, ignores `bool`
+    /// messages, replies with a `String` to `String` messages and stops on `u8` messages.
+    /// Messages that were not sent with an ask are not replied to.
+    async fn doubler(_: (), _: Context, message: Message) -> ActorResult<()> {
+        if let (Some(value), Some(reply_to)) = (message.content_as::<i32>(), message.reply_to()) {
+            reply_to.send_new(*value * 2)?;
+        } else if let Some(value) = message.content_as::<String>() {
+            message.reply_to().unwrap().send_arc(value)?;
+        } else if message.content_as::<u8>().is_some() {
+            return Ok(Status::stop(()));
+        }
+        Ok(Status::done(()))
+    }
+
+    /// Tests that asking an actor resolves to the reply or to the appropriate error.
+    #[test]
+    fn test_ask() {
+        init_test_log();
+        let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+        let aid = system.spawn().with((), doubler).unwrap();
+        let timeout = Duration::from_millis(200);
+
+        // Messages that are not sent with ask have nowhere to reply to.
+        assert!(Message::new(11).reply_to().is_none());
+
+        let reply = aid.ask_blocking::<i32>(Message::new(11), timeout);
+        assert_eq!(22, *reply.unwrap());
+
+        let reply = aid.ask_blocking::<i32>(Message::new(true), timeout);
+        assert_eq!(Err(AskError::TimedOut), reply);
+
+        let reply = aid.ask_blocking::<i32>(Message::new("Hi".to_string()), timeout);
+        assert_eq!(Err(AskError::UnexpectedReply), reply);
+
+        let reply = aid.ask_blocking::<i32>(Message::new(0_u8), timeout);
+        assert_eq!(Err(AskError::ActorStopped), reply);
+
+        let reply = aid.ask_blocking::<i32>(Message::new(11), timeout);
+        let error = AskError::SendFailed(AidError::ActorAlreadyStopped);
+        assert_eq!(Err(error), reply);
+
+        system.trigger_and_await_shutdown(None);
+    }
+
+    /// Tests that actors can ask other actors from within their processor.
+    #[test]
+    fn test_ask_from_actor() {
+        init_test_log();
+        let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+        let tracker = AssertCollect::new();
+        let t = tracker.clone();
+        let target = system.spawn().with((), doubler).unwrap();
+
+        let aid = system
+            .spawn()
+            .with(
+                target,
+                move |target: Aid, context: Context, message: Message| {
+                    let t = t.clone();
+                    async move {
+                        if let Some(value) = message.content_as::<i32>() {
+                            let timeout = Duration::from_millis(1000);
+                            let reply = target.ask::<i32>(Message::new(*value), timeout).await?;
+                            t.assert(*reply == *value * 2, "Reply should be double the value");
+                            context.system.trigger_shutdown();
+                        }
+                        Ok(Status::done(target))
+                    }
+                },
+            )
+            .unwrap();
+
+        aid.send_new(11).unwrap();
+        system.await_shutdown(None);
+        tracker.collect();
+    }
+
+    /// Tests that typed aids send their protocol to the actor and that they serialize the same
+    /// as the untyped aid so they can be used interchangeably on the wire.
+    #[test]
+    fn test_typed_aid() {
+        init_test_log();
+        let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+        system.init_current(); // Required by Aid serialization.
+
+        let aid: TypedAid<i32> = system.spawn().with_typed((), doubler).unwrap();
+        let timeout = Duration::from_millis(1000);
+        assert_eq!(22, *aid.ask_blocking::<i32>(11, timeout).unwrap());
+        aid.send(11).unwrap();
+        aid.send_arc(Arc::new(11)).unwrap();
+        await_received(aid.aid(), 4, 1000).unwrap();
+
+        let serialized = bincode::serialize(&aid).unwrap();
+        assert_eq!(bincode::serialize(aid.aid()).unwrap(), serialized);
+        let typed: TypedAid<i32> = bincode::deserialize(&serialized).unwrap();
+        let untyped: Aid = bincode::deserialize(&serialized).unwrap();
+        assert_eq!(aid, typed);
+        assert!(Aid::ptr_eq(&Aid::from(typed), &untyped));
+
+        system.trigger_and_await_shutdown(None);
+    }
+
+    /// Spawns an actor that records the hooks that it runs in `events` and handles messages with
+    /// the given handler.
+    fn spawn_hooked<F, R>(system: &ActorSystem, events: &Arc<Mutex<Vec<String>>>, f: F) -> Aid
+    where
+        F: Processor<(), R> + 'static,
+        R: Future<Output = ActorResult<()>> + Send + 'static,
+    {
+        let record = |events: &Arc<Mutex<Vec<String>>>, event: &'static str| {
+            let events = events.clone();
+            move |_: Context| async move { events.lock().unwrap().push(event.to_string()) }
+        };
+        system
+            .spawn()
+            .on_started(record(events, "started"))
+            .on_stopping(record(events, "stopping"))
+            .on_stopped(record(events, "stopped"))
+            .with((), f)
+            .unwrap()
+    }
+
+    /// Waits for the actor to record its `stopped` hook and asserts that all of the hooks ran
+    /// exactly once and in order.
+    fn assert_hooks_ran(events: &Arc<Mutex<Vec<String>>>) {
+        let start = Instant::now();
+        while events.lock().unwrap().last().map(String::as_str) != Some("stopped") {
+            if Instant::elapsed(&start) > Duration::from_millis(1000) {
+                break;
+            }
+            sleep(1);
+        }
+        assert_eq!(
+            vec!["started", "stopping", "stopped"],
+            *events.lock().unwrap()
+        );
+    }
+
+    /// Tests that the hooks are run when the actor stops itself.
+    #[test]
+    fn test_hooks_on_stop() {
+        init_test_log();
+        let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+        let events = Arc::new(Mutex::new(Vec::new()));
+
+        let aid = spawn_hooked(
+            &system,
+            &events,
+            |_: (), _: Context, msg: Message| async move {
+                match msg.content_as::<i32>() {
+                    Some(_) => Ok(Status::stop(())),
+                    None => Ok(Status::done(())),
+                }
+            },
+        );
+        aid.send_new(11_i32).unwrap();
+        assert_hooks_ran(&events);
+        assert!(!system.is_actor_alive(&aid));
+
+        system.trigger_and_await_shutdown(None);
+        assert_hooks_ran(&events);
+    }
+
+    /// Tests that the hooks are run when the actor returns an error or panics.
+    #[test]
+    fn test_hooks_on_error_and_panic() {
+        init_test_log();
+        let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+        let errors = Arc::new(Mutex::new(Vec::new()));
+        let panics = Arc::new(Mutex::new(Vec::new()));
+
+        let aid = spawn_hooked(
+            &system,
+            &errors,
+            |_: (), _: Context, msg: Message| async move {
+                match msg.content_as::<i32>() {
+                    Some(_) => Err(std::fmt::Error.into()),
+                    None => Ok(Status::done(())),
+                }
+            },
+        );
+        aid.send_new(11_i32).unwrap();
+        assert_hooks_ran(&errors);
+
+        let aid = spawn_hooked(
+            &system,
+            &panics,
+            |_: (), _: Context, msg: Message| async move {
+                match msg.content_as::<i32>() {
+                    Some(_) => panic!("I panicked"),
+                    None => Ok(Status::done(())),
+                }
+            },
+        );
+        aid.send_new(11_i32).unwrap();
+        assert_hooks_ran(&panics);
+
+        system.trigger_and_await_shutdown(None);
+    }
+
+    /// Tests that the hooks are run when the actor is stopped by the system, including when it
+    /// is stopped before it has processed any message.
+    #[test]
+    fn test_hooks_on_stop_actor() {
+        init_test_log();
+        let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+        let events = Arc::new(Mutex::new(Vec::new()));
+
+        let aid = spawn_hooked(&system, &events, simple_handler);
+        await_received(&aid, 1, 1000).unwrap();
+        system.stop_actor(&aid);
+        assert_hooks_ran(&events);
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let aid = spawn_hooked(&system, &events, simple_handler);
+        system.stop_actor(&aid);
+        assert_hooks_ran(&events);
+
+        system.trigger_and_await_shutdown(None);
+    }
+
+    /// Tests that the hooks of all actors are run when the actor system shuts down, even for
+    /// actors that are busy processing a message.
+    #[test]
+    fn test_hooks_on_shutdown() {
+        init_test_log();
+        let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+        let idle = Arc::new(Mutex::new(Vec::new()));
+        let busy = Arc::new(Mutex::new(Vec::new()));
+
+        let aid = spawn_hooked(&system, &idle, simple_handler);
+        await_received(&aid, 1, 1000).unwrap();
+
+        let aid = spawn_hooked(
+            &system,
+            &busy,
+            |_: (), _: Context, msg: Message| async move {
+                if msg.content_as::<i32>().is_some() {
+                    futures::future::pending::<()>().await;
+                }
+                Ok(Status::done(()))
+            },
+        );
+        aid.send_new(11_i32).unwrap();
+        sleep(50);
+
+        system.trigger_and_await_shutdown(None);
+        assert_hooks_ran(&idle);
+        assert_hooks_ran(&busy);
+    }
+
+    /// Spawns an actor from the `builder` that counts the [`SystemMsg::ReceiveTimeout`]s that
+    /// it receives and sets its receive timeout to the number of milliseconds in each `u64`
+    /// message that it receives, removing the timeout when the number is 0.
+    fn spawn_timeout_counter(builder: ActorBuilder, timeouts: &Arc<AtomicUsize>) -> Aid {
+        let timeouts = timeouts.clone();
+        builder
+            .with((), move |_: (), context: Context, message: Message| {
+                let timeouts = timeouts.clone();
+                async move {
+                    if let Some(millis) = message.content_as::<u64>() {
+                        let timeout = Some(Duration::from_millis(*millis))
+                            .filter(|timeout| *timeout > Duration::from_millis(0));
+                        context.set_receive_timeout(timeout);
+                        assert_eq!(timeout, context.receive_timeout());
+                    } else if let Some(msg) = message.content_as::<SystemMsg>() {
+                        if let SystemMsg::ReceiveTimeout = &*msg {
+                            timeouts.fetch_add(1, Ordering::SeqCst);
+                        }
+                    }
+                    Ok(Status::done(()))
+                }
+            })
+            .unwrap()
+    }
+
+    /// Tests that an actor is sent a receive timeout only when it stays idle for the duration
+    /// of its timeout, repeatedly while it stays idle, and that the timeout can be changed and
+    /// removed by the actor.
+    #[test]
+    fn test_receive_timeout() {
+        init_test_log();
+        let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+        let timeouts = Arc::new(AtomicUsize::new(0));
+
+        let builder = system.spawn().receive_timeout(Duration::from_millis(40));
+        let aid = spawn_timeout_counter(builder, &timeouts);
+        for _ in 0..10 {
+            aid.send_new(11_i32).unwrap();
+            sleep(10);
+        }
+        assert_eq!(0, timeouts.load(Ordering::SeqCst));
+
+        sleep(150);
+        assert!(timeouts.load(Ordering::SeqCst) >= 2);
+
+        aid.send_new(0_u64).unwrap();
+        sleep(50);
+        let count = timeouts.load(Ordering::SeqCst);
+        sleep(100);
+        assert_eq!(count, timeouts.load(Ordering::SeqCst));
+
+        aid.send_new(10_u64).unwrap();
+        sleep(100);
+        assert!(timeouts.load(Ordering::SeqCst) > count);
+        assert_eq!(0, system.dead_letters_total());
+
+        system.trigger_and_await_shutdown(None);
+    }
+
+    /// Tests that an actor without a receive timeout can set one itself.
+    #[test]
+    fn test_set_receive_timeout() {
+        init_test_log();
+        let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+        let timeouts = Arc::new(AtomicUsize::new(0));
+
+        let aid = spawn_timeout_counter(system.spawn(), &timeouts);
+        sleep(50);
+        assert_eq!(0, timeouts.load(Ordering::SeqCst));
+
+        aid.send_new(10_u64).unwrap();
+        sleep(100);
+        assert!(timeouts.load(Ordering::SeqCst) > 0);
+
+        system.trigger_and_await_shutdown(None);
+    }
+
+    /// Tests that an actor keeps processing messages while a piped future runs and receives
+    /// the output of the future once it completes, or the panic if the future panics.
+    #[test]
+    fn test_pipe_to() {
+        init_test_log();
+        let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+        let events = Arc::new(Mutex::new(Vec::new()));
+
+        let e = events.clone();
+        let aid = system
+            .spawn()
+            .with((), move |_: (), context: Context, message: Message| {
+                let events = e.clone();
+                async move {
+                    if let Some(value) = message.content_as::<i32>() {
+                        let value = *value;
+                        let sleep = context.sleep(Duration::from_millis(30));
+                        let future = async move {
+                            sleep.await;
+                            match value {
+                                0 => panic!("Zero"),
+                                value if value > 0 => Ok(value * 2),
+                                _ => Err("Negative".to_string()),
+                            }
+                        };
+                        context.pipe_to(future, context.aid.clone())?;
+                    } else if let Some(result) =
+                        message.content_as::<Result<Result<i32, String>, Panic>>()
+                    {
+                        let event = match &*result {
+                            Ok(output) => format!("{:?}", output),
+                            Err(panic) => format!("Panic: {}", panic),
+                        };
+                        events.lock().unwrap().push(event);
+                    } else if let Some(text) = message.content_as::<String>() {
+                        events.lock().unwrap().push(text.to_string());
+                    }
+                    Ok(Status::done(()))
+                }
+            })
+            .unwrap();
+
+        aid.send_new(11_i32).unwrap();
+        aid.send_new(-1_i32).unwrap();
+        aid.send_new(0_i32).unwrap();
+        aid.send_new("Ping".to_string()).unwrap();
+        await_received(&aid, 8, 1000).unwrap();
+        let events = events.lock().unwrap();
+        assert_eq!("Ping", events[0]);
+        assert!(events.contains(&"Ok(22)".to_string()));
+        assert!(events.contains(&"Err(\"Negative\")".to_string()));
+        assert!(events.contains(&"Panic: Zero".to_string()));
+
+        system.trigger_and_await_shutdown(None);
+    }
+
+    /// Spawns an actor and returns the [`Context`] passed to its processor with the start
+    /// message, for testing the methods of the context outside of the processor.
+    fn spawn_context(system: &ActorSystem) -> Context {
+        let captured = Arc::new(Mutex::new(None));
+        let actor_captured = captured.clone();
+        system
+            .spawn()
+            .with((), move |_: (), context: Context, _: Message| {
+                actor_captured.lock().unwrap().get_or_insert(context);
+                async { Ok(Status::done(())) }
+            })
+            .unwrap();
+        let start = Instant::now();
+        loop {
+            if let Some(context) = captured.lock().unwrap().take() {
+                return context;
+            }
+            assert!(start.elapsed() < Duration::from_millis(1000));
+            sleep(1);
+        }
+    }
+
+    /// Tests that spawned tasks finish when their future completes and that cancelled tasks
+    /// drop their future.
+    #[test]
+    fn test_spawn_task() {
+        init_test_log();
+        let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+        let context = spawn_context(&system);
+        let aid = context.aid.clone();
+
+        let (sender, receiver) = oneshot::channel();
+        let task = context
+            .spawn_task(async move { sender.send(11).unwrap() })
+            .unwrap();
+        assert_eq!(Ok(11), futures::executor::block_on(receiver));
+        let start = Instant::now();
+        while !task.is_finished() {
+            assert!(start.elapsed() < Duration::from_millis(1000));
+            sleep(1);
+        }
+
+        let sleep_future = context.sleep(Duration::from_secs(60));
+        let task = context.pipe_to(sleep_future, aid.clone()).unwrap();
+        task.cancel();
+        let start = Instant::now();
+        while !task.is_finished() {
+            assert!(start.elapsed() < Duration::from_millis(1000));
+            sleep(1);
+        }
+        assert_eq!(1, aid.received().unwrap());
+
+        system.trigger_and_await_shutdown(None);
+    }
+
+    /// Tests that closures passed to `spawn_blocking` run on the blocking threads and that
+    /// their results and panics are returned.
+    #[test]
+    fn test_spawn_blocking() {
+        init_test_log();
+        let config = ActorSystemConfig::default()
+            .thread_pool_size(1)
+            .blocking_pool_size(1);
+        let system = ActorSystem::create(config);
+        let context = spawn_context(&system);
+
+        let name = context.spawn_blocking(|| thread::current().name().map(String::from));
+        let name = futures::executor::block_on(name).unwrap().unwrap();
+        assert!(name.starts_with("BlockingReactor-"), "{}", name);
+
+        let panicked = context.spawn_blocking(|| panic!("Blocking panic"));
+        let error = futures::executor::block_on(panicked).unwrap_err();
+        let panic = error.downcast::<Panic>().unwrap();
+        assert_eq!("Blocking panic", panic.panic_payload);
+
+        system.trigger_and_await_shutdown(None);
+    }
 }
@@ -3,61 +3,109 @@
 use crate::actors::ActorStream;
 use crate::executor::thread_pool::AxiomThreadPool;
 use crate::prelude::*;
+use crossbeam_queue::SegQueue;
 use dashmap::DashMap;
 use futures::task::ArcWake;
-use futures::Stream;
+use futures::{Stream, StreamExt};
 use log::{debug, info, trace, warn};
-use std::collections::{BTreeMap, VecDeque};
+use once_cell::sync::OnceCell;
+use std::cell::UnsafeCell;
 use std::pin::Pin;
-use std::sync::{Arc, Condvar, Mutex, RwLock};
+use std::sync::atomic::{AtomicBool, AtomicU16, AtomicU32, AtomicU64, AtomicU8, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
 use std::task::{Context, Poll, Waker};
 use std::time::{Duration, Instant};
 
+mod policy;
+#[cfg(feature = "tokio-runtime")]
+mod runtime;
 mod thread_pool;
 
+pub use policy::{
+    ActorInfo, LeastActorsPolicy, Priority, PriorityPolicy, ReactorLoad, Reactors,
+    RoundRobinPolicy, Scheduling, SchedulingPolicy,
+};
+
 /// The Executor is responsible for the high-level scheduling of Actors. When an Actor is
-/// registered, it is wrapped in a Task and added to the sleep queue. When the Actor is woken by a
-/// sent message, the Executor will check load balancing data and queue it in the Reactor with the
-/// least load.
+/// registered, it is wrapped in a Task which sleeps in the Executor. When the Actor is woken by a
+/// sent message, the [`SchedulingPolicy`] of the Executor picks the Reactor to queue it in, unless
+/// it's sticky and the Reactor it last ran in isn't overloaded, where it stays until it runs out
+/// of messages. Reactors that run out of work steal
+/// woken Actors from the busiest Reactor of the same kind, so that an Actor doesn't wait behind a
+/// busy one while other Reactors idle. Actors spawned with [`ActorBuilder::blocking`] are queued
+/// in the blocking Reactors instead, so that they don't hold up the other Actors, and actors
+/// spawned with [`ActorBuilder::pinned`] each get a Reactor of their own.
 #[derive(Clone)]
 pub(crate) struct AxiomExecutor {
-    /// The system's "is shutting down" flag.
-    shutdown_triggered: Arc<(Mutex<bool>, Condvar)>,
+    /// Set once shutdown is triggered.
+    shutdown_triggered: Arc<AtomicBool>,
     /// Barrier to await shutdown on.
     thread_pool: Arc<AxiomThreadPool>,
-    /// Actors that have no messages available.
-    sleeping: Arc<DashMap<Aid, Task>>,
-    /// All Reactors owned by this Executor.
-    reactors: Arc<DashMap<u16, AxiomReactor>>,
-    /// Counting actors per reactor for even distribution of Actors.
-    actors_per_reactor: Arc<DashMap<u16, u32>>,
+    /// The Tasks of all of the Actors, looked up to wake an Actor that was sent a message.
+    tasks: Arc<DashMap<Aid, Arc<Task>>>,
+    /// All Reactors owned by this Executor, indexed by their ids, apart from the pinned ones.
+    reactors: Arc<OnceCell<Vec<AxiomReactor>>>,
+    /// The Reactors of the pinned Actors, which hold the Actors for their whole life.
+    pinned: Arc<DashMap<Aid, AxiomReactor>>,
+    /// Decides where the Actors are queued, in which order and for how long they run.
+    policy: Arc<dyn SchedulingPolicy>,
+    /// When awaiting shutdown times out, after which the shutdown thread of the tokio Reactors
+    /// stops waiting for the Actors that are still being polled. Set by `await_shutdown`.
+    #[cfg(feature = "tokio-runtime")]
+    shutdown_deadline: Arc<Mutex<Option<Instant>>>,
 }
 
 impl AxiomExecutor {
-    /// Creates a new Executor with the given actor system configuration. This will govern the
-    /// configuration of the executor.
-    pub(crate) fn new(shutdown_triggered: Arc<(Mutex<bool>, Condvar)>) -> Self {
+    /// Creates a new Executor that schedules the Actors with the `policy`. The Reactors are
+    /// created from the configuration of the actor system once it starts.
+    pub(crate) fn new(policy: Arc<dyn SchedulingPolicy>) -> Self {
         Self {
-            shutdown_triggered,
+            shutdown_triggered: Default::default(),
             thread_pool: Default::default(),
-            sleeping: Default::default(),
+            tasks: Default::default(),
             reactors: Default::default(),
-            actors_per_reactor: Default::default(),
+            pinned: Default::default(),
+            policy,
+            #[cfg(feature = "tokio-runtime")]
+            shutdown_deadline: Default::default(),
         }
     }
 
     /// Initializes the executor and starts the AxiomReactor instances based on the count of the
-    /// number of threads configured in the actor system. This must be called before any work can
-    /// be performed with the actor system.
+    /// number of threads configured in the actor system, followed by the blocking Reactors. This
+    /// must be called before any work can be performed with the actor system.
     pub(crate) fn init(&self, system: &ActorSystem) {
-        for i in 0..system.data.config.thread_pool_size {
-            let reactor = AxiomReactor::new(self.clone(), system, i);
-            self.reactors.insert(i, reactor.clone());
-            self.actors_per_reactor.insert(i, 0);
+        let config = &system.data.config;
+        #[cfg(feature = "tokio-runtime")]
+        {
+            if let Some(runtime) = &config.tokio_runtime {
+                return self.init_tokio(system, runtime);
+            }
+        }
+        let blocking = config.thread_pool_size..config.thread_pool_size + config.blocking_pool_size;
+        let reactors = (0..blocking.end)
+            .map(|i| {
+                let kind = if blocking.contains(&i) {
+                    ReactorKind::Blocking
+                } else {
+                    ReactorKind::Shared
+                };
+                AxiomReactor::new(self.clone(), system, i, kind)
+            })
+            .collect();
+        if self.reactors.set(reactors).is_err() {
+            panic!("Executor initialized twice");
+        }
+        info!("Spawning Reactors");
+        for reactor in self.reactors() {
+            let reactor = reactor.clone();
             let sys = system.clone();
-            info!("Spawning Reactors");
+            let prefix = match reactor.kind {
+                ReactorKind::Blocking => "BlockingReactor",
+                _ => "Reactor",
+            };
             self.thread_pool
-                .spawn(format!("Reactor-{}", reactor.name), move || {
+                .spawn(format!("{}-{}", prefix, reactor.name), move || {
                     sys.init_current();
                     futures::executor::enter().expect("Executor nested in other executor");
                     loop {
@@ -66,68 +114,278 @@ impl AxiomExecutor {
                             break;
                         }
                     }
+                    reactor.drain();
                 });
         }
+        self.schedule_all();
+    }
+
+    /// Schedules all of the Tasks, as Actors spawned before the Reactors existed may have been
+    /// sent messages already.
+    fn schedule_all(&self) {
+        let tasks: Vec<_> = self.tasks.iter().map(|t| t.value().clone()).collect();
+        tasks.iter().for_each(|task| self.schedule(task));
+    }
+
+    /// The Reactors of the Executor, which are empty until it's initialized.
+    fn reactors(&self) -> &[AxiomReactor] {
+        self.reactors
+            .get()
+            .map_or(&[], |reactors| reactors.as_slice())
     }
 
     /// This gives the ActorStream to the Executor to manage. This must be ran before any messages
     /// are sent to the Actor, else it will fail to be woken until after its registered.
     pub(crate) fn register_actor(&self, actor: ActorStream) {
         let id = actor.context.aid.clone();
-        let actor = Mutex::new(Box::pin(actor));
+        let system = actor.context.system.clone();
+        let pinned = if actor.pinned {
+            // Pinned Reactors are not counted with the others, so their id is never looked up.
+            let reactor = AxiomReactor::new(self.clone(), &system, u16::MAX, ReactorKind::Pinned);
+            Some(reactor)
+        } else {
+            None
+        };
+        let task = Arc::new(Task {
+            id: id.clone(),
+            blocking: actor.blocking,
+            sticky: actor.sticky,
+            priority: actor.priority,
+            actor: UnsafeCell::new(Box::pin(actor)),
+            state: AtomicU8::new(IDLE),
+            reactor: AtomicU16::new(NO_REACTOR),
+            pinned,
+            executor: self.clone(),
+        });
+        self.tasks.insert(id, task.clone());
+        if task.pinned.is_some() {
+            self.spawn_pinned(&system, task);
+        }
+    }
 
-        self.sleeping.insert(id.clone(), Task { id, actor });
+    /// Starts the thread of the Reactor of a pinned Actor, named after the Actor. The Actor stays
+    /// in the Reactor while it sleeps and the thread ends once the Actor finishes.
+    fn spawn_pinned(&self, system: &ActorSystem, task: Arc<Task>) {
+        let id = task.id.clone();
+        let reactor = task.pinned.clone().unwrap();
+        self.pinned.insert(id.clone(), reactor.clone());
+        let executor = self.clone();
+        let sys = system.clone();
+        debug!("Spawning Reactor for pinned Actor `{}`", id.name_or_uuid());
+        self.thread_pool
+            .spawn(format!("Actor-{}", id.name_or_uuid()), move || {
+                sys.init_current();
+                futures::executor::enter().expect("Executor nested in other executor");
+                // Pinned Actors can await the futures of the tokio runtime of the system, if any.
+                #[cfg(feature = "tokio-runtime")]
+                let _runtime = sys.config().tokio_runtime.as_ref().map(|r| r.enter());
+                // `AxiomReactor::thread` returns true if it's set to be ran again.
+                while reactor.thread() && task.state.load(Ordering::Acquire) != FINISHED {}
+                if task.claim() {
+                    executor.tasks.remove(&id);
+                    task.finish();
+                }
+                executor.pinned.remove(&id);
+            });
     }
 
     /// This wakes an ActorStream in the Executor which will cause its future to be polled. The Aid,
     /// through the ActorSystem, will call this on Message Send.
     pub(crate) fn wake(&self, id: Aid) {
+        // Once shutdown is triggered the Reactors drain the sleeping Actors instead.
+        if self.is_shutdown_triggered() {
+            return;
+        }
         trace!("Waking Actor `{}`", id.name_or_uuid());
-        // Pull the Task
-        let task = match self.sleeping.remove(&id) {
-            Some((_, task)) => task,
+        let task = match self.tasks.get(&id) {
+            Some(task) => Arc::clone(&task),
             None => {
-                debug!(
-                    "Actor `{}` not in Executor - already woken or stopped",
-                    id.name_or_uuid()
-                );
+                debug!("Actor `{}` not in Executor - stopped", id.name_or_uuid());
                 return;
             }
         };
-        // Get the optimal Reactor
-        let destination = self.get_reactor_with_least_actors();
-        // Increment the Reactor's Actor count
-        *self.actors_per_reactor.get_mut(&destination).unwrap() += 1;
-        // Insert in the Reactor
-        self.reactors.get(&destination).unwrap().insert(task);
+        self.schedule(&task);
     }
 
-    /// Iterates over the actors-per-reactor collection, and finds the Reactor with the least number
-    /// of Actors.
+    /// Queues the Task in a Reactor to be polled, unless it's queued or being polled already, in
+    /// which case it's polled again. A sleeping Task is queued in the optimal Reactor and a Task
+    /// that is waiting on a future is queued in the Reactor that it's in.
+    fn schedule(&self, task: &Arc<Task>) {
+        // Until the Reactors are created, Actors sleep regardless.
+        if task.pinned.is_none() && self.reactors.get().is_none() {
+            return;
+        }
+        let mut state = task.state.load(Ordering::Acquire);
+        loop {
+            let next = match state {
+                IDLE | WAITING => SCHEDULED,
+                RUNNING => NOTIFIED,
+                _ => return,
+            };
+            match task
+                .state
+                .compare_exchange_weak(state, next, Ordering::AcqRel, Ordering::Acquire)
+            {
+                Ok(_) => break,
+                Err(actual) => state = actual,
+            }
+        }
+        match state {
+            IDLE => {
+                // Get the optimal Reactor
+                let destination = match &task.pinned {
+                    Some(reactor) => reactor,
+                    None => self.get_reactor_for(task),
+                };
+                // Increment the Reactor's Actor count
+                destination.actors.fetch_add(1, Ordering::Relaxed);
+                task.reactor.store(destination.id, Ordering::Relaxed);
+                destination.push(task.clone());
+            }
+            WAITING => self.reactor_of(task).push(task.clone()),
+            // The Reactor that is polling the Task polls it again.
+            _ => (),
+        }
+    }
+
+    /// Checks the system's "is shutting down" flag.
+    fn is_shutdown_triggered(&self) -> bool {
+        self.shutdown_triggered.load(Ordering::Acquire)
+    }
+
+    /// Sets the "is shutting down" flag and notifies all of the Reactors, so they can end.
+    pub(crate) fn trigger_shutdown(&self) {
+        self.shutdown_triggered.store(true, Ordering::Release);
+        self.reactors().iter().for_each(AxiomReactor::notify);
+        self.pinned.iter().for_each(|r| r.notify());
+    }
+
+    /// The Reactors that can run a Task, which are the blocking Reactors if `blocking` is set and
+    /// the other Reactors if not. If there are no Reactors of the kind asked for, the others will
+    /// do. The Executor must have been initialized.
+    fn reactors_for(&self, blocking: bool) -> &[AxiomReactor] {
+        let reactors = self.reactors();
+        // The blocking Reactors come after the others.
+        let shared = reactors
+            .iter()
+            .take_while(|r| r.kind == ReactorKind::Shared)
+            .count();
+        let (shared, blocking_reactors) = reactors.split_at(shared);
+        let reactors = if blocking && !blocking_reactors.is_empty() || shared.is_empty() {
+            blocking_reactors
+        } else {
+            shared
+        };
+        assert!(!reactors.is_empty(), "Executor has no Reactors");
+        reactors
+    }
+
+    /// Picks the Reactor to queue a sleeping Task in, which is the Reactor it last ran in if the
+    /// Task is sticky and that Reactor isn't overloaded, or else the Reactor that the policy
+    /// places it in.
     ///
     /// Note: while this is a little race-y, it's acceptable. It will produce adequate distribution.
-    fn get_reactor_with_least_actors(&self) -> u16 {
-        let mut iter_state = (0u16, u32::max_value());
-        for i in self.actors_per_reactor.iter() {
-            if i.value() < &iter_state.1 {
-                iter_state = (*i.key(), *i.value());
+    fn get_reactor_for(&self, task: &Task) -> &AxiomReactor {
+        let reactors = self.reactors_for(task.blocking);
+        if task.sticky {
+            let last = task.reactor.load(Ordering::Relaxed);
+            if let Some(last) = reactors.iter().find(|r| r.id == last) {
+                if !last.is_overloaded(reactors) {
+                    return last;
+                }
             }
         }
-        iter_state.0
+        let index = self.policy.place(&task.info(), &Reactors(reactors));
+        &reactors[index % reactors.len()]
+    }
+
+    /// The Reactor that a Task, which isn't sleeping, is in.
+    fn reactor_of<'a>(&'a self, task: &'a Task) -> &'a AxiomReactor {
+        match &task.pinned {
+            Some(reactor) => reactor,
+            None => &self.reactors()[task.reactor.load(Ordering::Relaxed) as usize],
+        }
+    }
+
+    /// Takes a woken Actor from the Reactor of the same kind as the `thief` with the most woken
+    /// Actors, moving the Actor to the `thief`. Pinned Actors are never stolen.
+    fn steal(&self, thief: &AxiomReactor) -> Option<Arc<Task>> {
+        if thief.kind == ReactorKind::Pinned {
+            return None;
+        }
+        let victim = self
+            .reactors()
+            .iter()
+            .filter(|r| r.id != thief.id && r.kind == thief.kind)
+            .max_by_key(|r| r.run_queue.len())?;
+        let task = victim.run_queue.pop()?;
+        // Move the Actor over to the thief, which returns the Task when it's done.
+        victim.actors.fetch_sub(1, Ordering::Relaxed);
+        thief.actors.fetch_add(1, Ordering::Relaxed);
+        task.reactor.store(thief.id, Ordering::Relaxed);
+        victim.stolen.fetch_add(1, Ordering::Relaxed);
+        thief.steals.fetch_add(1, Ordering::Relaxed);
+        Some(task)
     }
 
-    /// When a Reactor is done with an task, it will be sent here, and the Executor will decrement
-    /// the Actor count for that Reactor.
-    fn return_task(&self, task: Task, reactor: &AxiomReactor) {
+    /// Notifies an idle Reactor of the same kind as the `busy` Reactor, so that it can steal
+    /// the Actors that are waiting on the busy Reactor.
+    fn notify_idle(&self, busy: &AxiomReactor) {
+        let idle = self
+            .reactors()
+            .iter()
+            .find(|r| r.kind == busy.kind && r.idle.load(Ordering::SeqCst));
+        if let Some(reactor) = idle {
+            reactor.notify();
+        }
+    }
+
+    /// Collects the metrics of the Reactors, in the order of their ids. The Reactors of pinned
+    /// Actors are left out.
+    pub(crate) fn reactor_metrics(&self) -> Vec<ReactorMetrics> {
+        self.reactors().iter().map(AxiomReactor::metrics).collect()
+    }
+
+    /// When a Reactor is done with a task that ran out of messages, it will be sent here, and the
+    /// Executor will decrement the Actor count for that Reactor and put the Actor to sleep. If the
+    /// Actor was woken in the meantime it stays in the Reactor instead. Returns whether the Actor
+    /// was put to sleep.
+    fn return_task(&self, task: &Arc<Task>, reactor: &AxiomReactor) -> bool {
         trace!(
             "Actor {} returned from Reactor {}",
             task.id.name_or_uuid(),
             reactor.name
         );
-        // Put the Task back.
-        self.sleeping.insert(task.id.clone(), task);
+        // Start the receive timeout of the Actor, if any, now that it's idle.
+        task.sleep();
         // Decrement the Reactor's Actor count.
-        *self.actors_per_reactor.get_mut(&reactor.id).unwrap() -= 1;
+        reactor.actors.fetch_sub(1, Ordering::Relaxed);
+        let sleeping =
+            task.state
+                .compare_exchange(RUNNING, IDLE, Ordering::AcqRel, Ordering::Acquire);
+        if sleeping.is_err() {
+            reactor.actors.fetch_add(1, Ordering::Relaxed);
+            task.state.store(RUNNING, Ordering::Release);
+            return false;
+        }
+        // If the Actor was stopped while it was in the Reactor, wake it so that it finishes.
+        if task.id.is_stopped() {
+            self.wake(task.id.clone());
+        }
+        true
+    }
+
+    /// When a Reactor is done with a stopped task, it will be sent here, and the Executor will
+    /// decrement the Actor count for that Reactor and drop the task.
+    fn finish_task(&self, task: &Task, reactor: &AxiomReactor) {
+        trace!(
+            "Actor {} finished in Reactor {}",
+            task.id.name_or_uuid(),
+            reactor.name
+        );
+        task.state.store(FINISHED, Ordering::Release);
+        reactor.actors.fetch_sub(1, Ordering::Relaxed);
+        self.tasks.remove(&task.id);
     }
 
     /// Block until the threads have finished shutting down. This MUST be called AFTER shutdown is
@@ -135,13 +393,13 @@ impl AxiomExecutor {
     pub(crate) fn await_shutdown(&self, timeout: impl Into<Option<Duration>>) -> ShutdownResult {
         let start = Instant::now();
         info!("Notifying Reactor threads, so they can end gracefully");
-        for r in self.reactors.iter() {
-            match r.thread_condvar.read() {
-                Ok(g) => g.1.notify_one(),
-                Err(_) => return ShutdownResult::Panicked,
-            }
-        }
+        self.reactors().iter().for_each(AxiomReactor::notify);
+        self.pinned.iter().for_each(|r| r.notify());
         let timeout = timeout.into().map(|t| t - (Instant::now() - start));
+        #[cfg(feature = "tokio-runtime")]
+        {
+            *self.shutdown_deadline.lock().unwrap() = timeout.map(|t| start + t);
+        }
         info!("Awaiting the threadpool's shutdown");
         self.thread_pool.await_shutdown(timeout)
     }
@@ -155,274 +413,485 @@ pub enum ShutdownResult {
     Panicked,
 }
 
+/// A snapshot of the work of one of the Reactor threads of an actor system, as returned by
+/// [`ActorSystem::reactor_metrics`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReactorMetrics {
+    /// The name of the Reactor, which its thread is named after.
+    pub name: String,
+    /// Whether the Reactor runs the actors spawned with
+    /// [`ActorBuilder::blocking`](crate::actors::ActorBuilder::blocking).
+    pub blocking: bool,
+    /// The number of actors that are currently in the Reactor.
+    pub actors: u32,
+    /// The number of woken actors waiting for the Reactor to poll them.
+    pub queued: usize,
+    /// The number of woken actors that the Reactor stole from other Reactors.
+    pub steals: u64,
+    /// The number of woken actors that other Reactors stole from this Reactor.
+    pub stolen: u64,
+}
+
+/// The kinds of Reactors, which differ in the Actors that they run.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum ReactorKind {
+    /// Runs any of the Actors that aren't blocking or pinned.
+    Shared,
+    /// Runs the Actors spawned with [`ActorBuilder::blocking`].
+    Blocking,
+    /// Runs a single Actor spawned with [`ActorBuilder::pinned`] on a thread of its own.
+    Pinned,
+}
+
 /// The Reactor is a wrapper for a worker thread. It contains the queues, locks, and other state
 /// information necessary to manage the work load and worker thread.
 ///
 /// Actors are added to the Reactor on waking, queued for polling. If they can be polled again, they
-/// are retained till they are depleted of messages or are stopped.
+/// are retained till they are depleted of messages or are stopped. The Reactor only holds on to
+/// the Tasks that are queued; Tasks that wait on a future are held by the wakers of the future,
+/// which queue them in the Reactor again.
 #[derive(Clone)]
 pub(crate) struct AxiomReactor {
     /// The ID of the Reactor
     id: u16,
+    /// The kind of Actors that this Reactor runs.
+    kind: ReactorKind,
     /// The diagnostic ID of this Reactor.
     name: String,
     /// The Executor that owns this Reactor.
     executor: AxiomExecutor,
-    /// The queue of Actors that are ready to be polled.
-    run_queue: Arc<RwLock<VecDeque<Wakeup>>>,
-    /// The queue of Actors this Reactor is responsible for.
-    wait_queue: Arc<RwLock<BTreeMap<Aid, Task>>>,
+    /// The lock-free queues of Actors that are ready to be polled.
+    run_queue: Arc<RunQueue>,
+    /// The number of Actors in this Reactor, for even distribution of Actors.
+    actors: Arc<AtomicU32>,
     /// This is used to pause/resume threads that run out of work.
-    thread_condvar: Arc<RwLock<(Mutex<()>, Condvar)>>,
+    thread_condvar: Arc<(Mutex<()>, Condvar)>,
     /// How long the thread waits on the thread_condvar before timing out and looping anyways.
     thread_wait_time: Duration,
-    /// How long to work on an Actor before moving on to the next Wakeup.
+    /// How long to work on an Actor before moving on to the next woken Actor.
     time_slice: Duration,
     /// If an `ActorStream::poll_next` takes longer than this, it will log a warning.
     warn_threshold: Duration,
-}
-
-// A little hack to dictate a loop from inside a function call.
-enum LoopResult<T> {
-    Ok(T),
-    Continue,
+    /// How many more Actors than the Reactor with the least this Reactor has to have for sticky
+    /// Actors to be moved to another Reactor.
+    sticky_threshold: u32,
+    /// Set while the thread waits on the thread_condvar for work, so that only then the
+    /// thread_condvar is notified.
+    idle: Arc<AtomicBool>,
+    /// The tokio runtime that runs the Actors of this Reactor as tasks, instead of the thread of
+    /// the Reactor.
+    #[cfg(feature = "tokio-runtime")]
+    runtime: Option<tokio::runtime::Handle>,
+    /// The number of woken Actors this Reactor stole from other Reactors.
+    steals: Arc<AtomicU64>,
+    /// The number of woken Actors other Reactors stole from this Reactor.
+    stolen: Arc<AtomicU64>,
 }
 
 impl AxiomReactor {
     /// Creates a new Reactor
-    fn new(executor: AxiomExecutor, system: &ActorSystem, id: u16) -> AxiomReactor {
+    fn new(
+        executor: AxiomExecutor,
+        system: &ActorSystem,
+        id: u16,
+        kind: ReactorKind,
+    ) -> AxiomReactor {
         let name = format!("{:08x?}-{}", system.data.uuid.as_fields().0, id);
         debug!("Creating Reactor {}", name);
 
         AxiomReactor {
             id,
+            kind,
             name,
             executor,
-            run_queue: Arc::new(RwLock::new(Default::default())),
-            wait_queue: Arc::new(RwLock::new(BTreeMap::new())),
-            thread_condvar: Arc::new(RwLock::new((Mutex::new(()), Condvar::new()))),
+            run_queue: Default::default(),
+            actors: Default::default(),
+            thread_condvar: Arc::new((Mutex::new(()), Condvar::new())),
             thread_wait_time: system.config().thread_wait_time,
             time_slice: system.config().time_slice,
             warn_threshold: system.config().warn_threshold,
+            sticky_threshold: system.config().sticky_threshold,
+            idle: Default::default(),
+            #[cfg(feature = "tokio-runtime")]
+            runtime: match kind {
+                ReactorKind::Pinned => None,
+                _ => system.config().tokio_runtime.clone(),
+            },
+            steals: Default::default(),
+            stolen: Default::default(),
         }
     }
 
-    /// Moves an Actor from the executor into a reactor.
-    fn insert(&self, task: Task) {
-        let token = Token {
-            id: task.id.clone(),
-            reactor: self.clone(),
-        };
-        let waker = futures::task::waker(Arc::new(token));
-        let wakeup = Wakeup {
-            id: task.id.clone(),
-            waker,
-        };
-        self.wait(task);
-        self.wake(wakeup);
-    }
-
     /// This is the core unit of work that drives the Reactor. The Executor should run this on an
     /// endless loop. Returns `false` when it should no longer be ran.
     pub(crate) fn thread(&self) -> bool {
         // If we're shutting down, quit.
-        {
-            if *self
-                .executor
-                .shutdown_triggered
-                .0
-                .lock()
-                .expect("Poisoned shutdown_triggered condvar")
-            {
-                debug!("Reactor-{} acknowledging shutdown", self.name);
-                return false;
-            }
+        if self.executor.is_shutdown_triggered() {
+            debug!("Reactor-{} acknowledging shutdown", self.name);
+            return false;
         }
 
-        let (w, mut task) = match self.get_work() {
-            LoopResult::Ok(v) => v,
-            LoopResult::Continue => return true,
-        };
-        let aid = w.id.clone();
+        if let Some(task) = self.get_work() {
+            trace!(
+                "Reactor-{} running Actor `{}`",
+                self.name,
+                task.id.name_or_uuid()
+            );
+            self.run(task);
+        }
+        true
+    }
+
+    /// Polls the Actor until it runs out of messages, waits on a future, stops or exceeds the
+    /// time slice, in which case it's queued again.
+    fn run(&self, task: Arc<Task>) {
+        // The Task is ours to poll until it leaves the RUNNING and NOTIFIED states, unless it was
+        // taken to be finished at shutdown.
+        let running =
+            task.state
+                .compare_exchange(SCHEDULED, RUNNING, Ordering::AcqRel, Ordering::Acquire);
+        if running.is_err() {
+            return;
+        }
+        // The threads of a tokio runtime may run the Actors of several actor systems.
+        #[cfg(feature = "tokio-runtime")]
+        let _current = self
+            .runtime
+            .as_ref()
+            .map(|_| unsafe { task.actor() }.context.system.enter());
+        let waker = futures::task::waker_ref(&task);
 
-        let end = Instant::now() + self.time_slice;
+        let policy = &self.executor.policy;
+        let end = Instant::now() + policy.time_slice(&task.info(), self.time_slice);
         loop {
             let start = Instant::now();
             // This polls the Actor as a Stream.
-            match task.poll(&w.waker) {
-                Poll::Ready(result) => {
-                    // Ready(None) indicates an empty message queue. Time to sleep.
-                    if let None = result {
-                        self.executor.return_task(task, self);
-                        break;
-                    }
+            match task.poll(&waker) {
+                Poll::Ready(Some(result)) => {
                     // The Actor should handle its own internal modifications in response to the
                     // result.
-                    let is_stopping = {
-                        task.actor
-                            .lock()
-                            .expect("Poisoned Actor")
-                            .handle_result(result.unwrap())
-                    };
-                    // It's dead, Jim.
-                    if is_stopping {
+                    task.handle_result(result);
+                }
+                // Ready(None) indicates an empty message queue or a finished Actor.
+                Poll::Ready(None) => {
+                    if task.is_finished() {
+                        // It's dead, Jim.
+                        self.executor.finish_task(&task, self);
                         break;
                     }
-                    // If we're past this timeslice, add back into the queues and move
-                    // to the next woken Actor. Else, poll it again.
-                    if Instant::now() >= end {
-                        self.wait(task);
-                        self.wake(w);
+                    // Time to sleep, unless it was woken in the meantime.
+                    if self.executor.return_task(&task, self) {
                         break;
                     }
+                    continue;
                 }
-                // Still pending, return to wait_queue. Drop the wakeup, because the futures
-                // will re-add it later through their wakers.
+                // Still pending, leave it to the wakers of the futures to queue it again, unless
+                // it was woken while it was polled.
                 Poll::Pending => {
                     trace!("Reactor-{} waiting on pending Actor", self.name);
-                    self.wait(task);
-                    break;
+                    let waiting = task.state.compare_exchange(
+                        RUNNING,
+                        WAITING,
+                        Ordering::AcqRel,
+                        Ordering::Acquire,
+                    );
+                    if waiting.is_ok() {
+                        break;
+                    }
+                    task.state.store(RUNNING, Ordering::Release);
+                    continue;
                 }
             }
-            if Instant::now().duration_since(start) >= self.warn_threshold {
+            // Blocking and pinned Actors don't hold up other Actors, so they are not warned about.
+            if self.kind == ReactorKind::Shared
+                && Instant::now().duration_since(start) >= self.warn_threshold
+            {
                 warn!(
                     "Actor {} took longer than configured warning threshold",
-                    aid.name_or_uuid()
+                    task.id.name_or_uuid()
                 );
             }
+            // If we're past this timeslice, add back into the queue and move to the next woken
+            // Actor. Else, poll it again.
+            if Instant::now() >= end {
+                task.state.store(SCHEDULED, Ordering::Release);
+                self.push(task);
+                break;
+            }
         }
-        true
     }
 
-    // If there's no Actors woken, the Reactor thread will block on the condvar. If there's a Wakeup
-    // without an Actor (which might happen due to an acceptable race condition), we can continue to
-    // the next woken Actor, and drop this Wakeup. Otherwise, we have the Wakeup and Task we need,
-    // and can continue.
-    //
-    // While this arrangement is a little dense, it saves a level of indentation.
+    // If there's no Actors woken, the Reactor will try to steal one from another Reactor, and if
+    // there's none to steal, the Reactor thread will block on the condvar.
     #[inline]
-    fn get_work(&self) -> LoopResult<(Wakeup, Task)> {
-        if let Some(w) = self.get_woken() {
-            if let Some(task) = self.remove_waiting(&w.id) {
-                trace!(
-                    "Reactor-{} received Wakeup for Actor `{}`",
-                    self.name,
-                    task.id.name_or_uuid()
-                );
-                LoopResult::Ok((w, task))
-            } else {
-                trace!("Reactor-{} dropping spurious WakeUp", self.name);
-                LoopResult::Continue
-            }
-        } else {
-            let (mutex, condvar) = &*self
-                .thread_condvar
-                .read()
-                .expect("Poisoned Reactor condvar");
+    fn get_work(&self) -> Option<Arc<Task>> {
+        if let Some(task) = self.run_queue.pop() {
+            return Some(task);
+        }
+        if let Some(task) = self.executor.steal(self) {
+            trace!(
+                "Reactor-{} stole Actor `{}`",
+                self.name,
+                task.id.name_or_uuid()
+            );
+            return Some(task);
+        }
 
+        let (mutex, condvar) = &*self.thread_condvar;
+        let g = mutex.lock().expect("Poisoned Reactor condvar");
+        self.idle.store(true, Ordering::SeqCst);
+        // Check again now that we're idle, as Actors queued from here on notify the condvar.
+        if self.run_queue.is_empty() && !self.executor.is_shutdown_triggered() {
             trace!("Reactor-{} waiting on condvar", self.name);
-            let g = mutex.lock().expect("Poisoned Reactor condvar");
             let _ = condvar
                 .wait_timeout(g, self.thread_wait_time)
                 .expect("Poisoned Reactor condvar");
             trace!("Reactor-{} resuming", self.name);
-            LoopResult::Continue
         }
+        self.idle.store(false, Ordering::SeqCst);
+        None
     }
 
-    /// Add an Actor's Wakeup to the run_queue.
-    fn wake(&self, wakeup: Wakeup) {
-        self.run_queue
-            .write()
-            .expect("Poisoned run_queue")
-            .push_back(wakeup);
-        self.thread_condvar
-            .read()
-            .expect("Poisoned Reactor condvar")
-            .1
-            .notify_one();
+    /// Add an Actor to the run_queue, unless the Reactor runs its Actors on a tokio runtime. If
+    /// the Reactor thread is busy, an idle Reactor is notified instead so that it can steal the
+    /// Actor.
+    fn push(&self, task: Arc<Task>) {
+        #[cfg(feature = "tokio-runtime")]
+        {
+            if let Some(runtime) = &self.runtime {
+                return self.spawn(runtime, task);
+            }
+        }
+        let priority = self.executor.policy.priority(&task.info());
+        self.run_queue.push(task, priority);
+        if self.idle.load(Ordering::SeqCst) {
+            self.notify();
+        } else if self.kind != ReactorKind::Pinned {
+            self.executor.notify_idle(self);
+        }
+    }
+
+    /// Wakes the Reactor thread if it's waiting on the thread_condvar.
+    fn notify(&self) {
+        let (mutex, condvar) = &*self.thread_condvar;
+        let _g = mutex.lock().expect("Poisoned Reactor condvar");
+        condvar.notify_one();
+    }
+
+    /// Whether this Reactor has so many more Actors than the least loaded of the `reactors` that
+    /// sticky Actors should be moved out of it.
+    fn is_overloaded(&self, reactors: &[AxiomReactor]) -> bool {
+        let actors = |r: &AxiomReactor| r.actors.load(Ordering::Relaxed);
+        let least = reactors.iter().map(actors).min().unwrap_or(0);
+        actors(self) >= least.saturating_add(self.sticky_threshold)
+    }
+
+    /// The current load of this Reactor, for the policy to place Actors by.
+    fn load(&self) -> ReactorLoad {
+        ReactorLoad {
+            actors: self.actors.load(Ordering::Relaxed),
+            queued: self.run_queue.len(),
+        }
+    }
+
+    /// Takes a snapshot of the metrics of this Reactor.
+    fn metrics(&self) -> ReactorMetrics {
+        ReactorMetrics {
+            name: self.name.clone(),
+            blocking: self.kind == ReactorKind::Blocking,
+            actors: self.actors.load(Ordering::Relaxed),
+            queued: self.run_queue.len(),
+            steals: self.steals.load(Ordering::Relaxed),
+            stolen: self.stolen.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Stops all of the sleeping and woken Actors, running each to completion so that their stop
+    /// hooks are invoked. This is called by the Reactor thread once shutdown is triggered. Actors
+    /// that are still being polled by another Reactor are left to that Reactor.
+    fn drain(&self) {
+        let tasks: Vec<_> = self
+            .executor
+            .tasks
+            .iter()
+            .filter(|t| t.pinned.is_none())
+            .map(|t| t.value().clone())
+            .collect();
+        for task in tasks {
+            if task.claim() {
+                self.executor.tasks.remove(&task.id);
+                task.finish();
+            }
+        }
+    }
+}
+
+/// The Actor is sleeping in the Executor.
+const IDLE: u8 = 0;
+/// The Actor is in a Reactor, waiting on a future.
+const WAITING: u8 = 1;
+/// The Actor is queued in the run_queue of a Reactor.
+const SCHEDULED: u8 = 2;
+/// The Actor is being polled by a Reactor.
+const RUNNING: u8 = 3;
+/// The Actor is being polled by a Reactor and was woken since, so it has to be polled again.
+const NOTIFIED: u8 = 4;
+/// The Actor has finished, or is being finished at shutdown.
+const FINISHED: u8 = 5;
+
+/// The id of the Reactor of a Task that hasn't been in a Reactor yet.
+const NO_REACTOR: u16 = u16::MAX;
+
+/// The queues of the Actors that are ready to be polled by a Reactor, one for each [`Priority`].
+#[derive(Default)]
+struct RunQueue {
+    low: SegQueue<Arc<Task>>,
+    normal: SegQueue<Arc<Task>>,
+    high: SegQueue<Arc<Task>>,
+}
+
+impl RunQueue {
+    /// Queues the Task behind the Tasks with the same `priority`.
+    fn push(&self, task: Arc<Task>, priority: Priority) {
+        match priority {
+            Priority::Low => self.low.push(task),
+            Priority::Normal => self.normal.push(task),
+            Priority::High => self.high.push(task),
+        }
     }
 
-    /// Pop the next Wakeup.
-    fn get_woken(&self) -> Option<Wakeup> {
-        self.run_queue
-            .write()
-            .expect("Poisoned run_queue")
-            .pop_front()
+    /// Takes the first Task with the highest priority.
+    fn pop(&self) -> Option<Arc<Task>> {
+        self.high
+            .pop()
+            .or_else(|| self.normal.pop())
+            .or_else(|| self.low.pop())
     }
 
-    /// Add a Task to the Reactor's wait_queue.
-    fn wait(&self, task: Task) {
-        self.wait_queue
-            .write()
-            .expect("Poisoned wait_queue")
-            .insert(task.id.clone(), task);
+    /// The number of queued Tasks.
+    fn len(&self) -> usize {
+        self.high.len() + self.normal.len() + self.low.len()
     }
 
-    /// Remove a Task from the Reactor's wait_queue.
-    fn remove_waiting(&self, id: &Aid) -> Option<Task> {
-        self.wait_queue
-            .write()
-            .expect("Poisoned wait_queue")
-            .remove(id)
+    /// Whether there are no queued Tasks.
+    fn is_empty(&self) -> bool {
+        self.high.is_empty() && self.normal.is_empty() && self.low.is_empty()
     }
 }
 
-/// Tasks represent the unit of work that an Executor-Reactor system is responsible for.
+/// Tasks represent the unit of work that an Executor-Reactor system is responsible for. A Task is
+/// shared by the Executor, the Reactor that it's queued in and the wakers of the Actor's futures,
+/// so it's also the waker of the Actor. Its state decides who gets to poll the Actor.
 struct Task {
     id: Aid,
-    actor: Mutex<Pin<Box<ActorStream>>>,
+    /// The Actor, which may only be accessed by the thread that moved the state to RUNNING or
+    /// FINISHED.
+    actor: UnsafeCell<Pin<Box<ActorStream>>>,
+    /// The scheduling state of the Task, which is one of the constants above.
+    state: AtomicU8,
+    /// The id of the Reactor that the Task is in, while it's not sleeping, or was last in.
+    reactor: AtomicU16,
+    /// Whether the Actor runs in the blocking Reactors.
+    blocking: bool,
+    /// Whether the Actor is queued in the Reactor it was last in when it's woken.
+    sticky: bool,
+    /// The priority the Actor was spawned with.
+    priority: Priority,
+    /// The Reactor of a pinned Actor, which it's always in.
+    pinned: Option<AxiomReactor>,
+    /// The Executor that schedules the Task when it's woken.
+    executor: AxiomExecutor,
 }
 
+// The actor is only accessed by the thread that owns the Task through its state.
+unsafe impl Sync for Task {}
+
 impl Task {
+    /// Gives access to the ActorStream.
+    ///
+    /// # Safety
+    /// Only the thread that moved the state to RUNNING or FINISHED may call this.
+    #[allow(clippy::mut_from_ref)]
+    unsafe fn actor(&self) -> &mut Pin<Box<ActorStream>> {
+        &mut *self.actor.get()
+    }
+
+    /// What the policy knows about the Actor.
+    fn info(&self) -> ActorInfo<'_> {
+        ActorInfo {
+            aid: &self.id,
+            priority: self.priority,
+        }
+    }
+
     /// Proxy poll into the ActorStream
-    fn poll(&mut self, waker: &Waker) -> Poll<Option<Result<Status, StdError>>> {
+    fn poll(&self, waker: &Waker) -> Poll<Option<Result<Status, StdError>>> {
         let mut ctx = Context::from_waker(waker);
+        unsafe { self.actor() }.as_mut().poll_next(&mut ctx)
+    }
 
-        self.actor
-            .lock()
-            .expect("Poisoned ActorStream")
-            .as_mut()
-            .poll_next(&mut ctx)
+    /// Proxy handle_result into the ActorStream.
+    fn handle_result(&self, result: Result<Status, StdError>) {
+        unsafe { self.actor() }.handle_result(result);
     }
-}
 
-/// Object used for generating our wakers.
-struct Token {
-    id: Aid,
-    reactor: AxiomReactor,
-}
+    /// Proxy sleep into the ActorStream.
+    fn sleep(&self) {
+        unsafe { self.actor() }.sleep();
+    }
 
-impl ArcWake for Token {
-    fn wake_by_ref(arc_self: &Arc<Self>) {
-        let id = arc_self.id.clone();
+    /// Checks if the ActorStream is finished.
+    fn is_finished(&self) -> bool {
+        unsafe { self.actor() }.is_finished()
+    }
 
-        let wakeup = Wakeup {
-            id,
-            waker: futures::task::waker(arc_self.clone()),
-        };
+    /// Takes the Task to finish it at shutdown, if it isn't being polled or finished already.
+    /// Returns whether the Task was taken.
+    fn claim(&self) -> bool {
+        let mut state = self.state.load(Ordering::Acquire);
+        while let IDLE | WAITING | SCHEDULED = state {
+            match self.state.compare_exchange_weak(
+                state,
+                FINISHED,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => return true,
+                Err(actual) => state = actual,
+            }
+        }
+        false
+    }
 
-        (arc_self.reactor).wake(wakeup);
+    /// Stops the ActorStream and runs it to completion, blocking the current thread. The Task must
+    /// have been claimed.
+    fn finish(&self) {
+        debug!("Finishing Actor `{}`", self.id.name_or_uuid());
+        let actor = unsafe { self.actor() };
+        actor.shutdown();
+        while futures::executor::block_on(actor.next()).is_some() {}
     }
 }
 
-/// Object representing the need to wake an Actor, to be enqueued for waking.
-struct Wakeup {
-    id: Aid,
-    waker: Waker,
+impl ArcWake for Task {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        arc_self.executor.schedule(arc_self);
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::executor::ShutdownResult;
+    use crate::executor::{ShutdownResult, IDLE, SCHEDULED, WAITING};
     use crate::prelude::*;
     use crate::tests::*;
     use log::*;
     use std::future::Future;
     use std::pin::Pin;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
     use std::task::Poll;
     use std::thread;
-    use std::time::Duration;
+    use std::time::{Duration, Instant};
 
     struct PendingNTimes {
         pending_count: u8,
@@ -459,6 +928,12 @@ mod tests {
         }
     }
 
+    /// The scheduling state of the Task of the actor.
+    fn state_of(system: &ActorSystem, aid: &Aid) -> u8 {
+        let task = system.executor().tasks.get(aid).unwrap();
+        task.state.load(Ordering::SeqCst)
+    }
+
     #[test]
     fn test_nested_futures_wakeup() {
         init_test_log();
@@ -514,54 +989,393 @@ mod tests {
         await_received(&aid, 1, 5).expect("Actor took too long to process Start");
         let _ = aid.send_new(()).unwrap();
         sleep(5);
-        {
-            let pending = system
-                .executor()
-                .reactors
-                .iter()
-                .nth(0)
-                .unwrap()
-                .wait_queue
-                .read()
-                .unwrap()
-                .len();
-            assert_eq!(pending, 1, "Actor should be pending");
-        }
+        assert_eq!(WAITING, state_of(&system, &aid), "Actor should be pending");
         await_received(&aid, 2, 30).expect("Actor failed to process message");
         sleep(20);
-        {
-            let pending = system
-                .executor()
-                .reactors
-                .iter()
-                .nth(0)
-                .unwrap()
-                .wait_queue
-                .read()
-                .unwrap()
-                .len();
-            assert_eq!(
-                pending, 0,
-                "Actor should be returned to the Executor by now"
-            );
+        assert_eq!(
+            IDLE,
+            state_of(&system, &aid),
+            "Actor should be returned to the Executor by now"
+        );
+        let running = system.executor().reactors()[0].run_queue.len();
+        assert_eq!(running, 0, "Actor should not be running again");
+        let sleeping = system
+            .executor()
+            .tasks
+            .iter()
+            .filter(|task| task.state.load(Ordering::SeqCst) == IDLE)
+            .count();
+        assert_eq!(sleeping, 3, "Actor was not returned to Executor");
+    }
+
+    /// Tests that blocking actors run on the blocking Reactors and don't hold up the other
+    /// actors while they block.
+    #[test]
+    fn test_blocking_actors() {
+        init_test_log();
+
+        let config = ActorSystemConfig::default()
+            .thread_pool_size(1)
+            .blocking_pool_size(1);
+        let system = ActorSystem::create(config);
+        let blocking = system
+            .spawn()
+            .blocking(true)
+            .with((), |_: (), _: Context, msg: Message| async move {
+                if msg.content_as::<SystemMsg>().is_some() {
+                    return Ok(Status::done(()));
+                }
+                let name = thread::current().name().map(String::from);
+                assert!(name.unwrap().starts_with("BlockingReactor-"));
+                sleep(500);
+                Ok(Status::done(()))
+            })
+            .unwrap();
+        let aid = system.spawn().with((), simple_handler).unwrap();
+        await_received(&blocking, 1, 1000).unwrap();
+        await_received(&aid, 1, 1000).unwrap();
+
+        blocking.send_new(()).unwrap();
+        sleep(10);
+        aid.send_new(11).unwrap();
+        await_received(&aid, 2, 100).expect("Actor was held up by the blocking actor");
+        await_received(&blocking, 2, 1000).unwrap();
+        assert!(
+            !blocking.is_stopped(),
+            "Blocking actor ran on the wrong thread"
+        );
+
+        system.trigger_and_await_shutdown(None);
+    }
+
+    /// Tests that an idle Reactor steals an Actor that is waiting behind a busy Actor.
+    #[test]
+    fn test_work_stealing() {
+        init_test_log();
+
+        // The idle Reactor would only wake up on its own after a second.
+        let config = ActorSystemConfig::default()
+            .thread_pool_size(2)
+            .blocking_pool_size(0)
+            .thread_wait_time(Duration::from_secs(1));
+        let system = ActorSystem::create(config);
+        let busy = system
+            .spawn()
+            .with((), |_: (), _: Context, msg: Message| async move {
+                if msg.content_as::<SystemMsg>().is_none() {
+                    sleep(500);
+                }
+                Ok(Status::done(()))
+            })
+            .unwrap();
+        let aid = system.spawn().with((), simple_handler).unwrap();
+        await_received(&busy, 1, 1000).unwrap();
+        await_received(&aid, 1, 1000).unwrap();
+        sleep(10);
+
+        let before = system.reactor_metrics();
+        busy.send_new(()).unwrap();
+        sleep(10);
+        let executor = system.executor();
+        let reactor = executor
+            .reactors()
+            .iter()
+            .find(|r| r.actors.load(Ordering::SeqCst) == 1)
+            .expect("Busy actor is not in a Reactor");
+        // Queue the Actor behind the busy Actor, where the Executor wouldn't put it.
+        let task = Arc::clone(&executor.tasks.get(&aid).unwrap());
+        task.state.store(SCHEDULED, Ordering::SeqCst);
+        task.reactor.store(reactor.id, Ordering::SeqCst);
+        reactor.actors.fetch_add(1, Ordering::SeqCst);
+        reactor.push(task);
+        aid.send_new(11).unwrap();
+        await_received(&aid, 2, 200).expect("Actor was not stolen");
+
+        let after = system.reactor_metrics();
+        assert_eq!(2, after.len());
+        let (victim, thief) = (reactor.id as usize, 1 - reactor.id as usize);
+        assert_eq!(1, after[victim].stolen - before[victim].stolen);
+        assert_eq!(1, after[thief].steals - before[thief].steals);
+
+        system.trigger_and_await_shutdown(None);
+    }
+
+    /// Tests that a sticky actor is woken in the Reactor that it last ran in, even when another
+    /// Reactor has fewer actors, unless its Reactor is overloaded, and that the actor's setting
+    /// overrides the one of the system.
+    #[test]
+    fn test_sticky_scheduling() {
+        init_test_log();
+
+        // Runs an actor, then runs it again with another actor in the Reactor that it ran in,
+        // returning whether it ran in the same Reactor both times.
+        fn stays(config: ActorSystemConfig, sticky: Option<bool>) -> bool {
+            let system = ActorSystem::create(config.thread_pool_size(2).blocking_pool_size(0));
+            let mut builder = system.spawn();
+            builder.sticky = sticky;
+            let aid = builder.with((), simple_handler).unwrap();
+            await_received(&aid, 1, 1000).unwrap();
+            sleep(10);
+
+            let executor = system.executor();
+            let task = Arc::clone(&executor.tasks.get(&aid).unwrap());
+            let first = task.reactor.load(Ordering::SeqCst);
+            let reactor = &executor.reactors()[first as usize];
+            reactor.actors.fetch_add(1, Ordering::SeqCst);
+            aid.send_new(11).unwrap();
+            await_received(&aid, 2, 1000).unwrap();
+            let second = task.reactor.load(Ordering::SeqCst);
+            reactor.actors.fetch_sub(1, Ordering::SeqCst);
+            system.trigger_and_await_shutdown(None);
+            first == second
         }
-        {
-            let running = system
-                .executor()
-                .reactors
-                .iter()
-                .nth(0)
-                .unwrap()
-                .run_queue
-                .read()
-                .unwrap()
-                .len();
-            assert_eq!(running, 0, "Actor should not be running again");
+
+        let config = ActorSystemConfig::default;
+        assert!(!stays(config(), None));
+        assert!(stays(config().sticky_scheduling(true), None));
+        assert!(stays(config(), Some(true)));
+        assert!(!stays(config().sticky_scheduling(true), Some(false)));
+        let overloaded = config().sticky_scheduling(true).sticky_threshold(1);
+        assert!(!stays(overloaded, None));
+    }
+
+    /// Tests where the built-in policies place actors and that a custom policy is used.
+    #[test]
+    fn test_scheduling_policies() {
+        init_test_log();
+
+        // Counts the actors it places, all of which it places on the first Reactor.
+        #[derive(Debug, Default)]
+        struct Counting(AtomicUsize);
+
+        impl SchedulingPolicy for Counting {
+            fn place(&self, _: &ActorInfo, _: &Reactors) -> usize {
+                self.0.fetch_add(1, Ordering::SeqCst);
+                0
+            }
+        }
+
+        let policy = Arc::new(Counting::default());
+        let config = ActorSystemConfig::default()
+            .thread_pool_size(2)
+            .blocking_pool_size(0)
+            .scheduling(Scheduling::Custom(policy.clone()));
+        let system = ActorSystem::create(config);
+        let aid = system.spawn().with((), simple_handler).unwrap();
+        await_received(&aid, 1, 1000).unwrap();
+        sleep(10);
+        let placed = policy.0.load(Ordering::SeqCst);
+        aid.send_new(11).unwrap();
+        await_received(&aid, 2, 1000).unwrap();
+        assert_eq!(placed + 1, policy.0.load(Ordering::SeqCst));
+        let executor = system.executor();
+        let task = Arc::clone(&executor.tasks.get(&aid).unwrap());
+
+        let info = task.info();
+        let reactors = Reactors(executor.reactors());
+        let round_robin = RoundRobinPolicy::default();
+        let placed: Vec<_> = (0..4)
+            .map(|_| round_robin.place(&info, &reactors))
+            .collect();
+        assert_eq!(vec![0, 1, 0, 1], placed);
+        assert_eq!(0, LeastActorsPolicy.place(&info, &reactors));
+        executor.reactors()[0].actors.fetch_add(1, Ordering::SeqCst);
+        assert_eq!(1, LeastActorsPolicy.place(&info, &reactors));
+        assert_eq!(1, PriorityPolicy.place(&info, &reactors));
+        executor.reactors()[0].actors.fetch_sub(1, Ordering::SeqCst);
+
+        system.trigger_and_await_shutdown(None);
+    }
+
+    /// Tests that the priority policy runs the woken actors with a higher priority first, while
+    /// the default policy runs them in the order they were woken.
+    #[test]
+    fn test_priority_scheduling() {
+        init_test_log();
+
+        // Wakes a low and a high priority actor while the only Reactor is busy, returning the
+        // order in which they ran.
+        fn order(scheduling: Scheduling) -> Vec<Priority> {
+            let config = ActorSystemConfig::default()
+                .thread_pool_size(1)
+                .blocking_pool_size(0)
+                .scheduling(scheduling);
+            let system = ActorSystem::create(config);
+            let ran = Arc::new(Mutex::new(Vec::new()));
+            let spawn = |priority: Priority| {
+                let ran = ran.clone();
+                system
+                    .spawn()
+                    .priority(priority)
+                    .with((), move |_: (), _: Context, msg: Message| {
+                        let ran = ran.clone();
+                        async move {
+                            if msg.content_as::<SystemMsg>().is_none() {
+                                ran.lock().unwrap().push(priority);
+                            }
+                            Ok(Status::done(()))
+                        }
+                    })
+                    .unwrap()
+            };
+            let low = spawn(Priority::Low);
+            let high = spawn(Priority::High);
+            let busy = system
+                .spawn()
+                .with((), |_: (), _: Context, msg: Message| async move {
+                    if msg.content_as::<SystemMsg>().is_none() {
+                        sleep(100);
+                    }
+                    Ok(Status::done(()))
+                })
+                .unwrap();
+            await_received(&low, 1, 1000).unwrap();
+            await_received(&high, 1, 1000).unwrap();
+            await_received(&busy, 1, 1000).unwrap();
+
+            busy.send_new(()).unwrap();
+            sleep(20);
+            low.send_new(()).unwrap();
+            high.send_new(()).unwrap();
+            await_received(&low, 2, 1000).unwrap();
+            await_received(&high, 2, 1000).unwrap();
+            system.trigger_and_await_shutdown(None);
+            let ran = ran.lock().unwrap().clone();
+            ran
+        }
+
+        assert_eq!(
+            vec![Priority::Low, Priority::High],
+            order(Scheduling::LeastActors)
+        );
+        assert_eq!(
+            vec![Priority::High, Priority::Low],
+            order(Scheduling::Priority)
+        );
+    }
+
+    /// Tests that pinned actors, including their hooks, run on a thread of their own which ends
+    /// when the actor stops.
+    #[test]
+    fn test_pinned_actors() {
+        init_test_log();
+
+        let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(1));
+        let stopped_on = Arc::new(Mutex::new(None));
+        let hook_stopped_on = stopped_on.clone();
+        let aid = system
+            .spawn()
+            .name("pinned")
+            .pinned(true)
+            .on_stopped(move |_: Context| async move {
+                *hook_stopped_on.lock().unwrap() = thread::current().name().map(String::from);
+            })
+            .with((), |_: (), _: Context, _: Message| async move {
+                assert_eq!(Some("Actor-pinned"), thread::current().name());
+                Ok(Status::done(()))
+            })
+            .unwrap();
+        for i in 0..10 {
+            aid.send_new(i).unwrap();
+            sleep(1);
+        }
+        await_received(&aid, 11, 1000).unwrap();
+        assert!(
+            system.is_actor_alive(&aid),
+            "Pinned actor ran on the wrong thread"
+        );
+        assert_eq!(1, system.executor().pinned.len());
+
+        // The thread ends once the actor has stopped.
+        system.stop_actor(&aid);
+        let start = Instant::now();
+        while !system.executor().pinned.is_empty() {
+            assert!(start.elapsed() < Duration::from_millis(1000));
+            sleep(1);
         }
         assert_eq!(
-            system.executor().sleeping.len(),
-            2,
-            "Actor was not returned to Executor"
+            Some("Actor-pinned".to_string()),
+            *stopped_on.lock().unwrap()
         );
+
+        system.trigger_and_await_shutdown(None);
+    }
+
+    /// Waits for the `counter` to reach `expected`, failing the test if it doesn't within the
+    /// given number of milliseconds.
+    fn await_count(counter: &AtomicUsize, expected: usize, timeout_ms: u64) {
+        let start = Instant::now();
+        while counter.load(Ordering::SeqCst) < expected {
+            assert!(
+                start.elapsed() < Duration::from_millis(timeout_ms),
+                "Stalled at {} of {}",
+                counter.load(Ordering::SeqCst),
+                expected
+            );
+            sleep(1);
+        }
+    }
+
+    /// Tests that actors which are sent messages while they are being returned to the
+    /// executor are woken, with the same pairs of actors bouncing messages and one thread
+    /// sending to many actors as the ping-pong and fan-out benchmarks. Before the run queues
+    /// were lock-free, such a wake-up could be lost and the ping-pong pairs would stall.
+    #[test]
+    fn test_no_lost_wakeups() {
+        init_test_log();
+
+        let config = ActorSystemConfig::default()
+            .thread_pool_size(4)
+            .message_channel_size(1024);
+        let system = ActorSystem::create(config);
+
+        // Pairs of actors bouncing a number back and forth until it reaches zero.
+        let finished = Arc::new(AtomicUsize::new(0));
+        let bounce = |finished: Arc<AtomicUsize>, context: Context, message: Message| async move {
+            if let Some(msg) = message.content_as::<(Aid, u32)>() {
+                let (sender, count) = &*msg;
+                if *count == 0 {
+                    finished.fetch_add(1, Ordering::SeqCst);
+                } else {
+                    sender.send_new((context.aid.clone(), count - 1))?;
+                }
+            }
+            Ok(Status::done(finished))
+        };
+        let pairs: Vec<(Aid, Aid)> = (0..8)
+            .map(|_| {
+                let ping = system.spawn().with(finished.clone(), bounce).unwrap();
+                let pong = system.spawn().with(finished.clone(), bounce).unwrap();
+                (ping, pong)
+            })
+            .collect();
+        for round in 1..=10 {
+            for (ping, pong) in pairs.iter() {
+                ping.send_new((pong.clone(), 1000_u32)).unwrap();
+            }
+            await_count(&finished, round * pairs.len(), 5000);
+        }
+
+        // One thread sending messages to many actors.
+        let counted = Arc::new(AtomicUsize::new(0));
+        let count = |counted: Arc<AtomicUsize>, _: Context, message: Message| async move {
+            if message.content_as::<u32>().is_some() {
+                counted.fetch_add(1, Ordering::SeqCst);
+            }
+            Ok(Status::done(counted))
+        };
+        let aids: Vec<Aid> = (0..1000)
+            .map(|_| system.spawn().with(counted.clone(), count).unwrap())
+            .collect();
+        for round in 1..=3 {
+            for i in 0..10_u32 {
+                for aid in aids.iter() {
+                    aid.send_new(i).unwrap();
+                }
+            }
+            await_count(&counted, round * aids.len() * 10, 5000);
+        }
+
+        system.trigger_and_await_shutdown(None);
     }
 }
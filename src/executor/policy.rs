@@ -0,0 +1,206 @@
+//! The policies that decide which Reactor a woken Actor is queued in, in which order the Reactors
+//! run the queued Actors and for how long.
+
+use crate::executor::AxiomReactor;
+use crate::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The priority of an actor, which the reactor threads use to order the woken actors that they
+/// have yet to run, depending on the [`SchedulingPolicy`] of the actor system. Set with
+/// [`ActorBuilder::priority`](crate::actors::ActorBuilder::priority).
+#[derive(
+    Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize, Deserialize,
+)]
+pub enum Priority {
+    /// Runs after all of the other woken actors.
+    Low,
+    /// The priority of actors, unless set otherwise.
+    #[default]
+    Normal,
+    /// Runs before all of the other woken actors.
+    High,
+}
+
+/// What a [`SchedulingPolicy`] knows about the actor that it's scheduling.
+#[derive(Debug)]
+pub struct ActorInfo<'a> {
+    /// The actor being scheduled.
+    pub aid: &'a Aid,
+    /// The priority the actor was spawned with.
+    pub priority: Priority,
+}
+
+/// The current load of a reactor thread, which a [`SchedulingPolicy`] places actors by.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ReactorLoad {
+    /// The number of awake actors in the reactor, including the ones waiting on a future.
+    pub actors: u32,
+    /// The number of woken actors waiting for the reactor to run them.
+    pub queued: usize,
+}
+
+/// The reactor threads that an actor can be queued in, which are the threads that run the
+/// blocking actors for actors spawned with
+/// [`ActorBuilder::blocking`](crate::actors::ActorBuilder::blocking) and the other threads for
+/// the rest. There's always at least one.
+pub struct Reactors<'a>(pub(crate) &'a [AxiomReactor]);
+
+impl<'a> Reactors<'a> {
+    /// The number of reactors.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Always false, as there's always at least one reactor.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The load of the reactor at the `index`.
+    pub fn load(&self, index: usize) -> ReactorLoad {
+        self.0[index].load()
+    }
+
+    /// The loads of all of the reactors, in order.
+    pub fn loads(&self) -> impl Iterator<Item = ReactorLoad> + 'a {
+        self.0.iter().map(AxiomReactor::load)
+    }
+}
+
+/// Decides how the reactor threads of an actor system run the actors. Whenever an actor that
+/// was sleeping is sent a message, the policy picks the reactor that it's queued in. Every time
+/// an actor is queued in a reactor the policy gives it a priority, and reactors run the actors
+/// with a higher priority first and the actors with the same priority in the order that they were
+/// queued. Once a reactor runs an actor, it keeps processing the messages of the actor until the
+/// time slice that the policy gives the actor runs out, at which point the actor is queued again.
+///
+/// Actors spawned with [`ActorBuilder::pinned`](crate::actors::ActorBuilder::pinned) always
+/// run on their own thread, and actors spawned with
+/// [`ActorBuilder::sticky`](crate::actors::ActorBuilder::sticky) stay on the reactor that they
+/// last ran on unless it's overloaded, so the policy only places them if they have to move. Idle
+/// reactors take woken actors from busy reactors regardless of the policy.
+///
+/// The built-in policies are selected with [`ActorSystemConfig::scheduling`] and others can be
+/// used with [`Scheduling::Custom`].
+///
+/// # Examples
+/// ```
+/// use axiom::prelude::*;
+/// use std::sync::Arc;
+///
+/// // Puts all of the actors on the first reactor, unless they are stolen.
+/// #[derive(Debug)]
+/// struct FirstReactor;
+///
+/// impl SchedulingPolicy for FirstReactor {
+///     fn place(&self, _: &ActorInfo, _: &Reactors) -> usize {
+///         0
+///     }
+/// }
+///
+/// let scheduling = Scheduling::Custom(Arc::new(FirstReactor));
+/// let system = ActorSystem::create(ActorSystemConfig::default().scheduling(scheduling));
+/// system.trigger_and_await_shutdown(None);
+/// ```
+pub trait SchedulingPolicy: Debug + Send + Sync {
+    /// Picks the index of the reactor, out of the `reactors` that can run the woken `actor`, to
+    /// queue it in. An index out of the bounds of the `reactors` wraps around.
+    fn place(&self, actor: &ActorInfo, reactors: &Reactors) -> usize;
+
+    /// The priority to queue the `actor` with, which is `Priority::Normal` by default so that
+    /// reactors run actors in the order that they were queued.
+    fn priority(&self, actor: &ActorInfo) -> Priority {
+        let _ = actor;
+        Priority::Normal
+    }
+
+    /// How long a reactor keeps processing the messages of the `actor` before moving on to the
+    /// next woken actor, which is the [`ActorSystemConfig::time_slice`] passed as `time_slice` by
+    /// default. Note that a reactor always processes at least one message.
+    fn time_slice(&self, actor: &ActorInfo, time_slice: Duration) -> Duration {
+        let _ = actor;
+        time_slice
+    }
+}
+
+/// Places actors on the reactor with the fewest awake actors and runs them in the order that they
+/// were woken. This is the default policy.
+#[derive(Debug, Default)]
+pub struct LeastActorsPolicy;
+
+impl SchedulingPolicy for LeastActorsPolicy {
+    fn place(&self, _: &ActorInfo, reactors: &Reactors) -> usize {
+        least_actors(reactors)
+    }
+}
+
+/// Places actors on the reactor with the fewest awake actors and runs the woken actors with the
+/// highest [`Priority`] first. Note that actors with a lower priority don't run at all while
+/// there are woken actors with a higher priority, apart from the ones taken by idle reactors.
+#[derive(Debug, Default)]
+pub struct PriorityPolicy;
+
+impl SchedulingPolicy for PriorityPolicy {
+    fn place(&self, _: &ActorInfo, reactors: &Reactors) -> usize {
+        least_actors(reactors)
+    }
+
+    fn priority(&self, actor: &ActorInfo) -> Priority {
+        actor.priority
+    }
+}
+
+/// Places the woken actors on the reactors in turn, regardless of their load, and runs them in
+/// the order that they were woken.
+#[derive(Debug, Default)]
+pub struct RoundRobinPolicy {
+    /// The number of actors placed so far, which picks the next reactor.
+    next: AtomicUsize,
+}
+
+impl SchedulingPolicy for RoundRobinPolicy {
+    fn place(&self, _: &ActorInfo, reactors: &Reactors) -> usize {
+        self.next.fetch_add(1, Ordering::Relaxed) % reactors.len()
+    }
+}
+
+/// The index of the reactor with the fewest awake actors, the first one if several have the same
+/// number.
+fn least_actors(reactors: &Reactors) -> usize {
+    reactors
+        .loads()
+        .enumerate()
+        .min_by_key(|(_, load)| load.actors)
+        .map_or(0, |(index, _)| index)
+}
+
+/// The [`SchedulingPolicy`] of an actor system, as set in [`ActorSystemConfig::scheduling`].
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub enum Scheduling {
+    /// The [`LeastActorsPolicy`], which is the default.
+    #[default]
+    LeastActors,
+    /// The [`PriorityPolicy`].
+    Priority,
+    /// The [`RoundRobinPolicy`].
+    RoundRobin,
+    /// A policy of the user's own, which can't be serialized.
+    #[serde(skip)]
+    Custom(Arc<dyn SchedulingPolicy>),
+}
+
+impl Scheduling {
+    /// Creates the policy to schedule the actors of a new actor system with.
+    pub(crate) fn policy(&self) -> Arc<dyn SchedulingPolicy> {
+        match self {
+            Scheduling::LeastActors => Arc::new(LeastActorsPolicy),
+            Scheduling::Priority => Arc::new(PriorityPolicy),
+            Scheduling::RoundRobin => Arc::new(RoundRobinPolicy::default()),
+            Scheduling::Custom(policy) => policy.clone(),
+        }
+    }
+}
@@ -0,0 +1,139 @@
+//! Runs the Actors as tasks of a tokio runtime, instead of on the threads of the Reactors.
+
+use crate::executor::{AxiomExecutor, AxiomReactor, ReactorKind, Task};
+use crate::prelude::*;
+use log::{info, warn};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use tokio::runtime::Handle;
+
+impl AxiomExecutor {
+    /// Initializes the Executor to run the Actors as tasks of the tokio `runtime`. There's a
+    /// Reactor for the blocking Actors and one for the others, which keep track of the Actors but
+    /// have no threads of their own. A single thread waits for shutdown to stop the Actors, for
+    /// no longer than the timeout given to `await_shutdown`.
+    pub(super) fn init_tokio(&self, system: &ActorSystem, runtime: &Handle) {
+        let reactors = vec![
+            AxiomReactor::new(self.clone(), system, 0, ReactorKind::Shared),
+            AxiomReactor::new(self.clone(), system, 1, ReactorKind::Blocking),
+        ];
+        if self.reactors.set(reactors).is_err() {
+            panic!("Executor initialized twice");
+        }
+        let reactor = self.reactors()[0].clone();
+        let sys = system.clone();
+        let runtime = runtime.clone();
+        info!("Spawning the shutdown thread of the tokio Reactors");
+        self.thread_pool
+            .spawn(format!("TokioReactor-{}", reactor.name), move || {
+                sys.init_current();
+                // The stop hooks of the Actors may use the runtime as well.
+                let _runtime = runtime.enter();
+                // `AxiomReactor::thread` returns true if it's set to be ran again.
+                while reactor.thread() {}
+                // The tasks still polling Actors put them to sleep once they are done.
+                let executor = &reactor.executor;
+                while executor.tasks.iter().any(|t| t.pinned.is_none()) {
+                    let deadline = *executor.shutdown_deadline.lock().unwrap();
+                    if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                        warn!("Shutdown timed out while Actors were still running");
+                        break;
+                    }
+                    reactor.drain();
+                    thread::sleep(Duration::from_millis(1));
+                }
+            });
+        self.schedule_all();
+    }
+}
+
+impl AxiomReactor {
+    /// Polls the Actor in a task of the tokio `runtime`, on the blocking threads of the runtime if
+    /// this is the Reactor of the blocking Actors.
+    pub(super) fn spawn(&self, runtime: &Handle, task: Arc<Task>) {
+        let executor = self.executor.clone();
+        let id = self.id as usize;
+        let run = move || executor.reactors()[id].run(task);
+        match self.kind {
+            ReactorKind::Blocking => {
+                runtime.spawn_blocking(run);
+            }
+            _ => {
+                runtime.spawn(async move { run() });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::tests::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::Duration;
+
+    /// Tests that the actors run on the threads of the tokio runtime, where they can await tokio
+    /// futures, and that they are stopped on shutdown as usual.
+    #[test]
+    fn test_tokio_runtime() {
+        init_test_log();
+
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(2)
+            .thread_name("tokio-worker")
+            .enable_time()
+            .build()
+            .unwrap();
+        let config = ActorSystemConfig::default().tokio_runtime(runtime.handle().clone());
+        let system = ActorSystem::create(config);
+        let stopped = Arc::new(AtomicBool::new(false));
+        let hook_stopped = stopped.clone();
+        let aid = system
+            .spawn()
+            .on_stopped(move |_: Context| async move {
+                hook_stopped.store(true, Ordering::SeqCst);
+            })
+            .with((), |_: (), context: Context, msg: Message| async move {
+                if msg.content_as::<SystemMsg>().is_none() {
+                    assert_eq!(Some("tokio-worker"), thread::current().name());
+                    assert_eq!(context.system.uuid(), ActorSystem::current().uuid());
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                }
+                Ok(Status::done(()))
+            })
+            .unwrap();
+        let blocking = system
+            .spawn()
+            .blocking(true)
+            .with((), |_: (), _: Context, msg: Message| async move {
+                if msg.content_as::<SystemMsg>().is_none() {
+                    assert_eq!(Some("tokio-worker"), thread::current().name());
+                    sleep(10);
+                }
+                Ok(Status::done(()))
+            })
+            .unwrap();
+        for i in 0..10 {
+            aid.send_new(i).unwrap();
+            blocking.send_new(i).unwrap();
+        }
+        await_received(&aid, 11, 1000).unwrap();
+        await_received(&blocking, 11, 1000).unwrap();
+        assert!(system.is_actor_alive(&aid), "Actor ran on the wrong thread");
+        assert!(
+            system.is_actor_alive(&blocking),
+            "Actor ran on the wrong thread"
+        );
+        assert_eq!(2, system.reactor_metrics().len());
+
+        system.trigger_shutdown();
+        assert_eq!(
+            ShutdownResult::Ok,
+            system.await_shutdown(Duration::from_millis(1000))
+        );
+        assert!(stopped.load(Ordering::SeqCst), "Stop hook didn't run");
+    }
+}
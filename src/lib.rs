@@ -220,18 +220,24 @@
 //! ground for copy-paste or throwaway code. The best tests will look like architected code.
 //! 7. **A huge emphasis is put on crate user ergonomics.** Axiom should be easy to use.
 
+use serde::{Deserialize, Serialize};
 use std::any::Any;
 use std::error::Error;
 use std::fmt::{Display, Formatter};
 
 // Re-export futures so the user doesn't need to import it.
 pub use futures;
+
+// Re-export the derive macros so the user doesn't need to depend on them separately.
+#[cfg(feature = "derive")]
+pub use axiom_derive::Dispatch;
 use prelude::*;
 
 pub mod actors;
 pub mod cluster;
 mod executor;
 pub mod message;
+pub mod supervisor;
 pub mod system;
 
 pub mod prelude;
@@ -243,7 +249,7 @@ pub type StdError = Box<dyn Error + Send + Sync + 'static>;
 /// A Result::Err is treated as a fatal error, and the Actor will be stopped.
 pub type ActorResult<State> = Result<(State, Status), StdError>;
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Panic {
     panic_payload: String,
 }
@@ -350,6 +356,26 @@ mod tests {
         Ok(())
     }
 
+    /// A utility that waits for an actor with the given name to be registered and returns an
+    /// `Ok<Aid>` when it is or an `Err<String>` if it isn't registered in time.
+    pub fn await_named(system: &ActorSystem, name: &str, timeout_ms: u64) -> Result<Aid, String> {
+        use std::time::Instant;
+        let start = Instant::now();
+        let duration = Duration::from_millis(timeout_ms);
+        loop {
+            if let Some(aid) = system.find_aid_by_name(name) {
+                return Ok(aid);
+            }
+            if Instant::elapsed(&start) > duration {
+                return Err(format!(
+                    "Timed out after {}ms! Actor `{}` was never registered",
+                    timeout_ms, name
+                ));
+            }
+            sleep(1);
+        }
+    }
+
     #[test]
     #[should_panic]
     fn test_assert_receive() {
@@ -1,5 +1,6 @@
 //! Defines the types associated with messages sent to actors.
 
+use crate::actors::Aid;
 use crate::AidError;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
@@ -91,6 +92,21 @@ impl<'de> Deserialize<'de> for MessageContent {
     }
 }
 
+/// The priority of a message, which orders the messages in a priority mailbox. See
+/// [`ActorBuilder::priority_mailbox`](crate::actors::ActorBuilder::priority_mailbox).
+#[derive(
+    Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize, Deserialize,
+)]
+pub enum MessagePriority {
+    /// Taken after all of the other messages.
+    Low,
+    /// The priority of messages, unless set otherwise.
+    #[default]
+    Normal,
+    /// Taken before all of the other messages, apart from system messages.
+    High,
+}
+
 /// Holds the data used in a message.
 #[derive(Serialize, Deserialize)]
 struct MessageData {
@@ -99,6 +115,19 @@ struct MessageData {
     /// The content of the message in a RwLock. The lock is needed because if the message
     /// came from remote, it will need to be converted to a local message variant.
     content: RwLock<MessageContent>,
+    /// The [`Aid`] that a reply to the message should be sent to, if the sender asked for one.
+    /// This isn't serialized with the message, so that the serialized form of messages stays the
+    /// same; it is sent to other actor systems in a [`WireMessage::ActorEnvelope`] instead.
+    ///
+    /// [`WireMessage::ActorEnvelope`]: crate::system::WireMessage::ActorEnvelope
+    #[serde(skip)]
+    reply_to: Option<Aid>,
+    /// The priority of the message in a priority mailbox, which is sent to other actor systems
+    /// in a [`WireMessage::ActorEnvelope`] like `reply_to`.
+    ///
+    /// [`WireMessage::ActorEnvelope`]: crate::system::WireMessage::ActorEnvelope
+    #[serde(skip)]
+    priority: MessagePriority,
 }
 
 /// A type for a message sent to an actor channel.
@@ -127,6 +156,8 @@ impl Message {
             data: Arc::new(MessageData {
                 type_id_hash: Message::hash_type_id::<T>(),
                 content: RwLock::new(MessageContent::Local(Arc::new(value))),
+                reply_to: None,
+                priority: MessagePriority::Normal,
             }),
         }
     }
@@ -151,10 +182,76 @@ impl Message {
             data: Arc::new(MessageData {
                 type_id_hash: Message::hash_type_id::<T>(),
                 content: RwLock::new(MessageContent::Local(value.clone())),
+                reply_to: None,
+                priority: MessagePriority::Normal,
+            }),
+        }
+    }
+
+    /// Creates a copy of this message that carries the given [`Aid`] as the place to send a
+    /// reply. The content is shared with the original message rather than copied.
+    pub(crate) fn with_reply_to(&self, reply_to: Aid) -> Message {
+        self.copy(Some(reply_to), self.data.priority)
+    }
+
+    /// Creates a copy of this message with the given priority, which decides when an actor with
+    /// a priority mailbox takes the message out of its mailbox. The content is shared with the
+    /// original message rather than copied. See
+    /// [`ActorBuilder::priority_mailbox`](crate::actors::ActorBuilder::priority_mailbox).
+    ///
+    /// # Examples
+    /// ```rust
+    /// use axiom::prelude::*;
+    ///
+    /// let msg = Message::new(11).with_priority(MessagePriority::High);
+    /// assert_eq!(MessagePriority::High, msg.priority());
+    /// ```
+    pub fn with_priority(&self, priority: MessagePriority) -> Message {
+        self.copy(self.data.reply_to.clone(), priority)
+    }
+
+    /// Returns the priority of the message, which is `MessagePriority::Normal` unless the
+    /// message was created with [`Message::with_priority`].
+    pub fn priority(&self) -> MessagePriority {
+        self.data.priority
+    }
+
+    /// Creates a copy of this message with the given `reply_to` and `priority`, which shares
+    /// the content of this message.
+    fn copy(&self, reply_to: Option<Aid>, priority: MessagePriority) -> Message {
+        let content = match &*self.data.content.read().unwrap() {
+            MessageContent::Local(content) => MessageContent::Local(content.clone()),
+            MessageContent::Remote(content) => MessageContent::Remote(content.clone()),
+        };
+        Message {
+            data: Arc::new(MessageData {
+                type_id_hash: self.data.type_id_hash,
+                content: RwLock::new(content),
+                reply_to,
+                priority,
             }),
         }
     }
 
+    /// Returns the [`Aid`] that a reply to this message should be sent to. This is only set
+    /// when the message was sent with [`Aid::ask`] or [`Aid::ask_blocking`]; messages sent any
+    /// other way will return a `None`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use axiom::prelude::*;
+    ///
+    /// async fn echo(_: (), _: Context, message: Message) -> ActorResult<()> {
+    ///     if let (Some(value), Some(reply_to)) = (message.content_as::<i32>(), message.reply_to()) {
+    ///         reply_to.send_arc(value)?;
+    ///     }
+    ///     Ok(Status::done(()))
+    /// }
+    /// ```
+    pub fn reply_to(&self) -> Option<Aid> {
+        self.data.reply_to.clone()
+    }
+
     /// A helper that will return the hash of the type id for `T`.
     #[inline]
     fn hash_type_id<T: 'static>() -> u64 {
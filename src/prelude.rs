@@ -1,14 +1,41 @@
 pub use crate::actors::Aid;
 pub use crate::actors::AidError;
+pub use crate::actors::AskError;
 pub use crate::actors::Context;
+pub use crate::actors::Dispatch;
+pub use crate::actors::Handler;
+pub use crate::actors::MailboxOverflow;
+pub use crate::actors::StashError;
 pub use crate::actors::Status;
+pub use crate::actors::TaskHandle;
+pub use crate::actors::TypedAid;
+pub use crate::executor::ActorInfo;
+pub use crate::executor::LeastActorsPolicy;
+pub use crate::executor::Priority;
+pub use crate::executor::PriorityPolicy;
+pub use crate::executor::ReactorLoad;
+pub use crate::executor::ReactorMetrics;
+pub use crate::executor::Reactors;
+pub use crate::executor::RoundRobinPolicy;
+pub use crate::executor::Scheduling;
+pub use crate::executor::SchedulingPolicy;
 pub use crate::executor::ShutdownResult;
 pub use crate::message::Message;
+pub use crate::message::MessagePriority;
 pub use crate::system::ActorSystem;
 pub use crate::system::ActorSystemConfig;
+pub use crate::system::DeadLetter;
+pub use crate::system::DeadLetterReason;
+pub use crate::system::ScheduleHandle;
+pub use crate::system::Sleep;
+pub use crate::system::StopReason;
 pub use crate::system::SystemError;
 pub use crate::system::SystemMsg;
+pub use crate::system::Timeout;
+pub use crate::system::TimeoutError;
 pub use crate::system::WireMessage;
 pub use crate::ActorResult;
 pub use crate::Panic;
 pub use crate::StdError;
+#[cfg(feature = "derive")]
+pub use axiom_derive::Dispatch;
@@ -0,0 +1,646 @@
+//! Implements supervisors that restart failed actors in the style of Erlang/OTP.
+//!
+//! A [`Supervisor`] is an actor that owns a list of [`ChildSpec`]s. When the supervisor starts it
+//! spawns one child for each spec, in order, as its own children and monitors them. Named
+//! children are thus registered under the name of the supervisor, such as `supervisor/worker`.
+//! When a child stops the supervisor consults the child's [`Restart`] policy and its own
+//! [`RestartStrategy`] to decide which children to restart. If children fail more often than
+//! the configured restart intensity allows, the supervisor stops all of its children and then
+//! stops itself with an error so that the failure escalates to whatever is supervising the
+//! supervisor.
+//!
+//! Supervisors are spawned like any other actor:
+//!
+//! ```rust
+//! use axiom::prelude::*;
+//! use axiom::supervisor::*;
+//!
+//! async fn worker(_: (), _: Context, _: Message) -> ActorResult<()> {
+//!     Ok(Status::done(()))
+//! }
+//!
+//! let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+//!
+//! let supervisor = Supervisor::new(RestartStrategy::OneForOne)
+//!     .child(ChildSpec::new(|| ((), worker)).name("worker"));
+//!
+//! system
+//!     .spawn()
+//!     .name("supervisor")
+//!     .with(supervisor, Supervisor::processor)
+//!     .unwrap();
+//! ```
+
+use crate::actors::{ActorBuilder, Processor};
+use crate::prelude::*;
+use log::{debug, error, warn};
+use std::collections::VecDeque;
+use std::error::Error;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Determines which children a [`Supervisor`] restarts when one of its children stops.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RestartStrategy {
+    /// Only the child that stopped is restarted.
+    OneForOne,
+    /// All of the children are stopped and then restarted in the order they were declared.
+    OneForAll,
+    /// The child that stopped and every child declared after it are stopped and then restarted
+    /// in the order they were declared.
+    RestForOne,
+}
+
+/// Determines whether a child should be restarted when it stops.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Restart {
+    /// The child is always restarted. This is the default.
+    Permanent,
+    /// The child is restarted only if it stopped abnormally, such as with an error, a panic or
+    /// by being killed. See [`StopReason::is_normal`].
+    Transient,
+    /// The child is never restarted.
+    Temporary,
+}
+
+/// Errors produced by a [`Supervisor`]. These are returned from the supervisor's processor and
+/// will thus be seen as the error in the [`SystemMsg::Stopped`] sent to anyone monitoring the
+/// supervisor.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SupervisorError {
+    /// The children of the supervisor were restarted more times than allowed in the configured
+    /// period so the supervisor gave up.
+    MaxRestartsExceeded,
+}
+
+impl std::fmt::Display for SupervisorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Error for SupervisorError {}
+
+/// The type of the function that spawns a child using a builder prepared by the supervisor.
+type ChildFactory = dyn Fn(ActorBuilder) -> Result<Aid, SystemError> + Send + Sync;
+
+/// The type of the hook that a supervisor runs before restarting a child.
+type RestartHook = dyn Fn(&Context) + Send + Sync;
+
+/// Describes how a [`Supervisor`] creates one of its children. Because a child may be started
+/// many times over the life of the supervisor, the spec holds a factory that produces a fresh
+/// state and processor for each start rather than the state itself.
+#[derive(Clone)]
+pub struct ChildSpec {
+    /// The optional name the child will be spawned with.
+    name: Option<String>,
+    /// The optional size of the child's message channel.
+    channel_size: Option<u16>,
+    /// The restart policy for this child.
+    restart: Restart,
+    /// Spawns the child given a builder.
+    factory: Arc<ChildFactory>,
+    /// The optional hook run before the child is restarted.
+    on_restart: Option<Arc<RestartHook>>,
+}
+
+impl ChildSpec {
+    /// Creates a new spec using the given factory which is called every time the child is
+    /// started and must return the starting state and the processor for the child. The child
+    /// will be [`Restart::Permanent`] unless changed with [`ChildSpec::restart`].
+    pub fn new<G, F, S, R>(factory: G) -> ChildSpec
+    where
+        G: Fn() -> (S, F) + Send + Sync + 'static,
+        S: Send + Sync + 'static,
+        R: Future<Output = ActorResult<S>> + Send + 'static,
+        F: Processor<S, R> + 'static,
+    {
+        ChildSpec {
+            name: None,
+            channel_size: None,
+            restart: Restart::Permanent,
+            factory: Arc::new(move |builder: ActorBuilder| {
+                let (state, processor) = factory();
+                builder.with(state, processor)
+            }),
+            on_restart: None,
+        }
+    }
+
+    /// Set the name that the child will be spawned with.
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Set the size of the child's message channel. See [`ActorBuilder::channel_size`].
+    pub fn channel_size(mut self, size: u16) -> Self {
+        self.channel_size = Some(size);
+        self
+    }
+
+    /// Set the restart policy for the child.
+    pub fn restart(mut self, restart: Restart) -> Self {
+        self.restart = restart;
+        self
+    }
+
+    /// Set a hook that the supervisor runs, with its own context, before it starts the child
+    /// again after the child stopped or was stopped by the supervisor to restart it. The hook
+    /// isn't run when the child is first started. Note that a restart is otherwise a stop and a
+    /// fresh start, so the [`ActorBuilder::on_stopping`] and [`ActorBuilder::on_started`] hooks
+    /// of the child are run as well.
+    pub fn on_restart<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&Context) + Send + Sync + 'static,
+    {
+        self.on_restart = Some(Arc::new(hook));
+        self
+    }
+
+    /// Spawns the child as a child of the actor with the given context, returning the [`Aid`]
+    /// of the new child.
+    fn start(&self, context: &Context) -> Result<Aid, SystemError> {
+        let mut builder = context.spawn();
+        if let Some(name) = &self.name {
+            builder = builder.name(name.clone());
+        }
+        if let Some(size) = self.channel_size {
+            builder = builder.channel_size(size);
+        }
+        (self.factory)(builder)
+    }
+}
+
+impl std::fmt::Debug for ChildSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "ChildSpec{{name: {:?}, channel_size: {:?}, restart: {:?}}}",
+            self.name, self.channel_size, self.restart
+        )
+    }
+}
+
+/// The state of a supervisor actor. The user builds the supervisor with its children and then
+/// spawns it with [`Supervisor::processor`] as the processor.
+pub struct Supervisor {
+    /// The strategy used to pick the children to restart.
+    strategy: RestartStrategy,
+    /// The maximum number of restarts allowed within `within`.
+    max_restarts: usize,
+    /// The period over which restarts are counted.
+    within: Duration,
+    /// The specs of the children in the order they are started.
+    specs: Vec<ChildSpec>,
+    /// The currently running children, in the same order as `specs`. A `None` means the child
+    /// isn't currently running.
+    children: Vec<Option<Aid>>,
+    /// The times of the recent restarts, used to enforce the restart intensity.
+    restarts: VecDeque<Instant>,
+}
+
+impl Supervisor {
+    /// Creates a supervisor with no children that will use the given strategy. By default the
+    /// supervisor will allow 3 restarts within 5 seconds before giving up.
+    pub fn new(strategy: RestartStrategy) -> Supervisor {
+        Supervisor {
+            strategy,
+            max_restarts: 3,
+            within: Duration::from_secs(5),
+            specs: Vec::new(),
+            children: Vec::new(),
+            restarts: VecDeque::new(),
+        }
+    }
+
+    /// Adds a child to the supervisor. Children are started in the order they are added and
+    /// stopped in the reverse order.
+    pub fn child(mut self, spec: ChildSpec) -> Self {
+        self.specs.push(spec);
+        self.children.push(None);
+        self
+    }
+
+    /// Sets the restart intensity of the supervisor. If more than `count` restarts happen
+    /// within the `within` period the supervisor stops all of its children and then stops
+    /// itself with [`SupervisorError::MaxRestartsExceeded`].
+    pub fn max_restarts(mut self, count: usize, within: Duration) -> Self {
+        self.max_restarts = count;
+        self.within = within;
+        self
+    }
+
+    /// The processor function for the supervisor actor.
+    pub async fn processor(mut self, context: Context, message: Message) -> ActorResult<Self> {
+        if let Some(msg) = message.content_as::<SystemMsg>() {
+            match &*msg {
+                SystemMsg::Start => {
+                    for index in 0..self.specs.len() {
+                        self.start_child(&context, index)?;
+                    }
+                    Ok(Status::done(self))
+                }
+                SystemMsg::Stop => {
+                    self.stop_children(&context, 0);
+                    Ok(Status::done(self))
+                }
+                SystemMsg::Stopped { aid, reason } => {
+                    self.child_stopped(&context, aid, !reason.is_normal())?;
+                    Ok(Status::done(self))
+                }
+                SystemMsg::ReceiveTimeout => Ok(Status::done(self)),
+            }
+        } else {
+            warn!("[{}] Supervisor received unhandled message.", context.aid);
+            Ok(Status::done(self))
+        }
+    }
+
+    /// Handles the notification that a child has stopped, restarting children as needed.
+    fn child_stopped(
+        &mut self,
+        context: &Context,
+        aid: &Aid,
+        failed: bool,
+    ) -> Result<(), StdError> {
+        // Children the supervisor stopped itself are no longer in `children` so their
+        // notifications are simply ignored here.
+        let index = match self.children.iter().position(|c| c.as_ref() == Some(aid)) {
+            Some(index) => index,
+            None => return Ok(()),
+        };
+        self.children[index] = None;
+
+        let restart = match self.specs[index].restart {
+            Restart::Permanent => true,
+            Restart::Transient => failed,
+            Restart::Temporary => false,
+        };
+        if !restart {
+            debug!("[{}] Not restarting child {}", context.aid, aid);
+            return Ok(());
+        }
+
+        // Enforce the restart intensity before restarting anything.
+        let now = Instant::now();
+        self.restarts.push_back(now);
+        while let Some(oldest) = self.restarts.front() {
+            if now.duration_since(*oldest) > self.within {
+                self.restarts.pop_front();
+            } else {
+                break;
+            }
+        }
+        if self.restarts.len() > self.max_restarts {
+            error!(
+                "[{}] Supervisor exceeded {} restarts in {:?}, giving up",
+                context.aid, self.max_restarts, self.within
+            );
+            self.stop_children(context, 0);
+            return Err(SupervisorError::MaxRestartsExceeded.into());
+        }
+
+        match self.strategy {
+            RestartStrategy::OneForOne => self.restart_child(context, index),
+            RestartStrategy::OneForAll => self.restart_children(context, 0),
+            RestartStrategy::RestForOne => self.restart_children(context, index),
+        }
+    }
+
+    /// Stops the children from `from` onwards and starts them again, skipping any temporary
+    /// children which are just stopped.
+    fn restart_children(&mut self, context: &Context, from: usize) -> Result<(), StdError> {
+        self.stop_children(context, from);
+        for index in from..self.specs.len() {
+            if self.specs[index].restart != Restart::Temporary {
+                self.restart_child(context, index)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Runs the restart hook of the child at the given index, if it has one, and then starts
+    /// the child.
+    fn restart_child(&mut self, context: &Context, index: usize) -> Result<(), StdError> {
+        if let Some(hook) = &self.specs[index].on_restart {
+            hook(context);
+        }
+        self.start_child(context, index)
+    }
+
+    /// Starts the child at the given index and monitors it.
+    fn start_child(&mut self, context: &Context, index: usize) -> Result<(), StdError> {
+        let aid = self.specs[index].start(context)?;
+        debug!("[{}] Started child {}", context.aid, aid);
+        self.children[index] = Some(aid.clone());
+        context.system.monitor(&context.aid, &aid);
+        Ok(())
+    }
+
+    /// Stops the running children from `from` onwards in reverse order.
+    fn stop_children(&mut self, context: &Context, from: usize) {
+        for child in self.children[from..].iter_mut().rev() {
+            if let Some(aid) = child.take() {
+                debug!("[{}] Stopping child {}", context.aid, aid);
+                context.system.stop_actor(&aid);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+    use serde::{Deserialize, Serialize};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Commands sent to the workers used in these tests.
+    #[derive(Debug, Serialize, Deserialize)]
+    enum Command {
+        /// Causes the worker to return an error.
+        Fail,
+        /// Causes the worker to stop normally.
+        Finish,
+    }
+
+    /// A worker that fails or stops on command.
+    async fn worker(_: (), _: Context, message: Message) -> ActorResult<()> {
+        if let Some(msg) = message.content_as::<Command>() {
+            match &*msg {
+                Command::Fail => Err("Told to fail".into()),
+                Command::Finish => Ok(Status::stop(())),
+            }
+        } else {
+            Ok(Status::done(()))
+        }
+    }
+
+    /// Waits for an actor with the given name to be registered that is not `previous`, which
+    /// is how the tests detect that a child has been restarted.
+    fn await_restarted(system: &ActorSystem, name: &str, previous: &Aid) -> Aid {
+        let start = Instant::now();
+        loop {
+            if let Some(aid) = system.find_aid_by_name(name) {
+                if aid != *previous {
+                    return aid;
+                }
+            }
+            if start.elapsed() > Duration::from_millis(1000) {
+                panic!("Timed out waiting for {} to be restarted", name);
+            }
+            sleep(1);
+        }
+    }
+
+    /// Creates a supervisor with the given strategy and three workers named `a`, `b` and `c`.
+    fn three_workers(strategy: RestartStrategy) -> Supervisor {
+        Supervisor::new(strategy)
+            .child(ChildSpec::new(|| ((), worker)).name("a"))
+            .child(ChildSpec::new(|| ((), worker)).name("b"))
+            .child(ChildSpec::new(|| ((), worker)).name("c"))
+    }
+
+    /// Tests that a one-for-one supervisor restarts only the child that failed.
+    #[test]
+    fn test_one_for_one() {
+        init_test_log();
+
+        let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+        let supervisor = three_workers(RestartStrategy::OneForOne);
+        system
+            .spawn()
+            .name("sup")
+            .with(supervisor, Supervisor::processor)
+            .unwrap();
+
+        let a = await_named(&system, "sup/a", 1000).unwrap();
+        let b = await_named(&system, "sup/b", 1000).unwrap();
+        let c = await_named(&system, "sup/c", 1000).unwrap();
+
+        b.send_new(Command::Fail).unwrap();
+        let new_b = await_restarted(&system, "sup/b", &b);
+        assert!(system.is_actor_alive(&new_b));
+        assert!(!system.is_actor_alive(&b));
+        assert!(system.is_actor_alive(&a));
+        assert!(system.is_actor_alive(&c));
+
+        system.trigger_and_await_shutdown(None);
+    }
+
+    /// Tests that a one-for-all supervisor restarts all of its children when one fails.
+    #[test]
+    fn test_one_for_all() {
+        init_test_log();
+
+        let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+        let supervisor = three_workers(RestartStrategy::OneForAll);
+        system
+            .spawn()
+            .name("sup")
+            .with(supervisor, Supervisor::processor)
+            .unwrap();
+
+        let a = await_named(&system, "sup/a", 1000).unwrap();
+        let b = await_named(&system, "sup/b", 1000).unwrap();
+        let c = await_named(&system, "sup/c", 1000).unwrap();
+
+        b.send_new(Command::Fail).unwrap();
+        await_restarted(&system, "sup/a", &a);
+        await_restarted(&system, "sup/b", &b);
+        await_restarted(&system, "sup/c", &c);
+        assert!(!system.is_actor_alive(&a));
+        assert!(!system.is_actor_alive(&c));
+
+        system.trigger_and_await_shutdown(None);
+    }
+
+    /// Tests that a rest-for-one supervisor restarts the failed child and the children declared
+    /// after it but leaves the children declared before it alone.
+    #[test]
+    fn test_rest_for_one() {
+        init_test_log();
+
+        let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+        let supervisor = three_workers(RestartStrategy::RestForOne);
+        system
+            .spawn()
+            .name("sup")
+            .with(supervisor, Supervisor::processor)
+            .unwrap();
+
+        let a = await_named(&system, "sup/a", 1000).unwrap();
+        let b = await_named(&system, "sup/b", 1000).unwrap();
+        let c = await_named(&system, "sup/c", 1000).unwrap();
+
+        b.send_new(Command::Fail).unwrap();
+        await_restarted(&system, "sup/b", &b);
+        await_restarted(&system, "sup/c", &c);
+        assert!(system.is_actor_alive(&a));
+        assert!(!system.is_actor_alive(&c));
+
+        system.trigger_and_await_shutdown(None);
+    }
+
+    /// Tests that the restart hook of each restarted child is run once per restart and not
+    /// when the children are first started.
+    #[test]
+    fn test_on_restart() {
+        init_test_log();
+
+        let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+        let restarts = Arc::new(AtomicUsize::new(0));
+        let spec = |name: &str| {
+            let restarts = restarts.clone();
+            ChildSpec::new(|| ((), worker))
+                .name(name)
+                .on_restart(move |_: &Context| {
+                    restarts.fetch_add(1, Ordering::SeqCst);
+                })
+        };
+        let supervisor = Supervisor::new(RestartStrategy::OneForAll)
+            .child(spec("a"))
+            .child(spec("b"));
+        system
+            .spawn()
+            .name("sup")
+            .with(supervisor, Supervisor::processor)
+            .unwrap();
+
+        let a = await_named(&system, "sup/a", 1000).unwrap();
+        let b = await_named(&system, "sup/b", 1000).unwrap();
+        assert_eq!(0, restarts.load(Ordering::SeqCst));
+
+        b.send_new(Command::Fail).unwrap();
+        await_restarted(&system, "sup/a", &a);
+        await_restarted(&system, "sup/b", &b);
+        assert_eq!(2, restarts.load(Ordering::SeqCst));
+
+        system.trigger_and_await_shutdown(None);
+    }
+
+    /// Tests that transient children are only restarted on failure and temporary children are
+    /// never restarted.
+    #[test]
+    fn test_restart_policies() {
+        init_test_log();
+
+        let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+        let supervisor = Supervisor::new(RestartStrategy::OneForOne)
+            .child(
+                ChildSpec::new(|| ((), worker))
+                    .name("transient")
+                    .restart(Restart::Transient),
+            )
+            .child(
+                ChildSpec::new(|| ((), worker))
+                    .name("temporary")
+                    .restart(Restart::Temporary),
+            );
+        system
+            .spawn()
+            .name("sup")
+            .with(supervisor, Supervisor::processor)
+            .unwrap();
+
+        // A transient child is restarted when it fails but not when it finishes normally.
+        let transient = await_named(&system, "sup/transient", 1000).unwrap();
+        transient.send_new(Command::Fail).unwrap();
+        let transient = await_restarted(&system, "sup/transient", &transient);
+        transient.send_new(Command::Finish).unwrap();
+        await_received(&transient, 2, 1000).unwrap();
+        sleep(20);
+        assert_eq!(None, system.find_aid_by_name("sup/transient"));
+
+        // A temporary child is not restarted even when it fails.
+        let temporary = await_named(&system, "sup/temporary", 1000).unwrap();
+        temporary.send_new(Command::Fail).unwrap();
+        await_received(&temporary, 2, 1000).unwrap();
+        sleep(20);
+        assert_eq!(None, system.find_aid_by_name("sup/temporary"));
+
+        system.trigger_and_await_shutdown(None);
+    }
+
+    /// Tests that a supervisor exceeding its restart intensity stops its children and then
+    /// stops itself with an error that is visible to anyone monitoring it.
+    #[test]
+    fn test_max_restarts_escalates() {
+        init_test_log();
+
+        let tracker = AssertCollect::new();
+        let t = tracker.clone();
+        let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+        let supervisor = Supervisor::new(RestartStrategy::OneForOne)
+            .max_restarts(1, Duration::from_secs(5))
+            .child(ChildSpec::new(|| ((), worker)).name("a"));
+        let supervisor_aid = system
+            .spawn()
+            .name("sup")
+            .with(supervisor, Supervisor::processor)
+            .unwrap();
+
+        let monitor = system
+            .spawn()
+            .with((), move |_: (), _: Context, message: Message| {
+                let t = t.clone();
+                async move {
+                    if let Some(msg) = message.content_as::<SystemMsg>() {
+                        if let SystemMsg::Stopped { reason, .. } = &*msg {
+                            let expected = StopReason::Error("MaxRestartsExceeded".to_string());
+                            t.assert(*reason == expected, "Expected the supervisor to fail");
+                        }
+                    }
+                    Ok(Status::done(()))
+                }
+            })
+            .unwrap();
+        system.monitor(&monitor, &supervisor_aid);
+
+        let a = await_named(&system, "sup/a", 1000).unwrap();
+        a.send_new(Command::Fail).unwrap();
+        let a = await_restarted(&system, "sup/a", &a);
+        a.send_new(Command::Fail).unwrap();
+
+        await_received(&monitor, 2, 1000).unwrap();
+        assert!(!system.is_actor_alive(&supervisor_aid));
+        assert_eq!(None, system.find_aid_by_name("sup/a"));
+
+        system.trigger_and_await_shutdown(None);
+        tracker.collect();
+    }
+
+    /// Tests that a supervisor that gives up is restarted by its own supervisor, which in turn
+    /// restarts the children of the failed supervisor.
+    #[test]
+    fn test_nested_supervisors() {
+        init_test_log();
+
+        let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+        let root = Supervisor::new(RestartStrategy::OneForOne).child(
+            ChildSpec::new(|| {
+                let inner = Supervisor::new(RestartStrategy::OneForOne)
+                    .max_restarts(0, Duration::from_secs(5))
+                    .child(ChildSpec::new(|| ((), worker)).name("a"));
+                (inner, Supervisor::processor)
+            })
+            .name("inner"),
+        );
+        system
+            .spawn()
+            .name("sup")
+            .with(root, Supervisor::processor)
+            .unwrap();
+
+        let inner = await_named(&system, "sup/inner", 1000).unwrap();
+        let a = await_named(&system, "sup/inner/a", 1000).unwrap();
+        a.send_new(Command::Fail).unwrap();
+
+        await_restarted(&system, "sup/inner", &inner);
+        await_restarted(&system, "sup/inner/a", &a);
+
+        system.trigger_and_await_shutdown(None);
+    }
+}
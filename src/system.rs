@@ -9,7 +9,7 @@
 //!
 //! The user should refer to test cases and examples as "how-to" guides for using Axiom.
 
-use crate::actors::{Actor, ActorBuilder, ActorStream};
+use crate::actors::{Actor, ActorBuilder, ActorStream, Hooks};
 use crate::executor::AxiomExecutor;
 use crate::prelude::*;
 use crate::system::system_actor::SystemActor;
@@ -18,7 +18,8 @@ use log::{debug, error, info, trace, warn};
 use once_cell::sync::OnceCell;
 use secc::{SeccReceiver, SeccSender};
 use serde::{Deserialize, Serialize};
-use std::collections::{BinaryHeap, HashSet};
+use std::cell::RefCell;
+use std::collections::HashSet;
 use std::error::Error;
 use std::fmt;
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -28,13 +29,20 @@ use std::thread::JoinHandle;
 use std::time::{Duration, Instant};
 use uuid::Uuid;
 
+mod dead_letters;
 mod system_actor;
+mod timer;
+
+pub use dead_letters::{DeadLetter, DeadLetterReason};
+use dead_letters::{DeadLetters, DeadLettersMessage};
+use timer::Timer;
+pub use timer::{ScheduleHandle, Sleep, Timeout, TimeoutError};
 
 // Holds an ActorSystem in a std::thread_local so that the Aid deserializer and other types can
 // obtain a clone if needed at any time. This needs to be set by each Reactor that is processing
 // messages with the actors.
 std::thread_local! {
-    static ACTOR_SYSTEM: OnceCell<ActorSystem> = OnceCell::new();
+    static ACTOR_SYSTEM: RefCell<Option<ActorSystem>> = RefCell::new(None);
 }
 
 /// An enum containing messages that are sent to actors by the actor system itself and are
@@ -52,8 +60,67 @@ pub enum SystemMsg {
     Stop,
 
     /// A message sent to an actor when a monitored actor is stopped and thus not able to
-    /// process additional messages. The value is the `aid` of the actor that stopped.
-    Stopped { aid: Aid, error: Option<String> },
+    /// process additional messages. The value is the `aid` of the actor that stopped and the
+    /// reason that it stopped.
+    Stopped { aid: Aid, reason: StopReason },
+
+    /// A message sent to an actor that hasn't received any other message for the duration of
+    /// its receive timeout. The message is sent again every time that the timeout elapses while
+    /// the actor stays idle. See [`ActorBuilder::receive_timeout`].
+    ReceiveTimeout,
+}
+
+/// The reason that an actor stopped, sent to monitoring actors in [`SystemMsg::Stopped`].
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum StopReason {
+    /// The actor stopped itself by returning [`Status::Stop`], usually in response to a
+    /// [`SystemMsg::Stop`].
+    Normal,
+    /// The actor returned an error from its message processor. The value is the formatted error.
+    Error(String),
+    /// The actor panicked in its message processor. The value is the panic payload.
+    Panic(String),
+    /// The actor was stopped from the outside using [`ActorSystem::stop_actor`].
+    Killed,
+    /// The actor was stopped because the actor system or the parent of the actor stopped.
+    Shutdown,
+    /// The actor system that the actor lives on was disconnected so the actor can't be reached.
+    NodeDown,
+    /// The actor was already stopped when it was monitored or linked so the reason it stopped
+    /// is unknown.
+    NotAlive,
+    /// The actor was stopped because the actor with the given [`Aid`] that it was linked to
+    /// stopped abnormally. See [`ActorSystem::link`].
+    Linked(Aid),
+    /// The actor was stopped because it didn't receive any message for the duration of its
+    /// receive timeout. See [`ActorBuilder::stop_on_receive_timeout`].
+    ReceiveTimeout,
+}
+
+impl StopReason {
+    /// Determines if the actor stopped in an orderly manner, meaning the reason is either
+    /// [`StopReason::Normal`], [`StopReason::Shutdown`] or [`StopReason::ReceiveTimeout`].
+    pub fn is_normal(&self) -> bool {
+        matches!(
+            self,
+            StopReason::Normal | StopReason::Shutdown | StopReason::ReceiveTimeout
+        )
+    }
+}
+
+impl From<StdError> for StopReason {
+    fn from(error: StdError) -> Self {
+        match error.downcast::<Panic>() {
+            Ok(panic) => StopReason::Panic(panic.panic_payload),
+            Err(error) => StopReason::Error(format!("{}", error)),
+        }
+    }
+}
+
+impl std::fmt::Display for StopReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
 }
 
 /// A type used for sending messages to other actor systems.
@@ -84,12 +151,30 @@ pub enum WireMessage {
         /// The message to be sent.
         message: Message,
     },
+    /// A container for a message from one actor on one system to an actor on another system
+    /// along with what isn't serialized with the message, which is the [`Aid`] to reply to and
+    /// the priority. It's only used for messages that are asked or have a priority other than
+    /// `MessagePriority::Normal`, so actor systems that don't know this variant can still take
+    /// every other message.
+    ActorEnvelope {
+        /// The UUID of the [`Aid`] that the message is being sent to.
+        actor_uuid: Uuid,
+        /// The UUID of the system that the destination [`Aid`] is local to.
+        system_uuid: Uuid,
+        /// The message to be sent.
+        message: Message,
+        /// The [`Aid`] that a reply to the message should be sent to.
+        reply_to: Option<Aid>,
+        /// The priority of the message.
+        priority: MessagePriority,
+    },
 }
 
 /// Configuration structure for the Axiom actor system. Note that this configuration implements
 /// serde serialize and deserialize to allow users to read the config from any serde supported
-/// means.
+/// means. Fields that are missing when deserializing take their default values.
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ActorSystemConfig {
     /// The default size for the channel that is created for each actor. This can be overridden on
     /// a per-actor basis during spawning as well. Making the default channel size bigger allows
@@ -109,6 +194,13 @@ pub struct ActorSystemConfig {
     /// over-schedule the CPU on the target hardware. The default value is 4 * the number of logical
     /// CPUs.
     pub thread_pool_size: u16,
+    /// The number of additional worker threads that run the actors spawned with
+    /// [`ActorBuilder::blocking`] and the closures passed to [`Context::spawn_blocking`], so
+    /// that blocking work such as file I/O doesn't hold up the other actors. If this is zero,
+    /// blocking actors run on the other worker threads. The default value is 1, which keeps
+    /// blocking work apart from the other actors at the cost of a single thread; systems that
+    /// do a lot of blocking work in parallel should raise it.
+    pub blocking_pool_size: u16,
     /// The threshold at which the dispatcher thread will warn the user that the message took too
     /// long to process. If this warning is being logged then the user probably should reconsider
     /// how their message processing works and refactor big tasks into a number of smaller tasks.
@@ -128,6 +220,39 @@ pub struct ActorSystemConfig {
     /// Determines whether the actor system will immediately start when it is created. The default
     /// value is true.
     pub start_on_launch: bool,
+    /// The resolution of the timer that sends the messages scheduled with [`Aid::send_after`]
+    /// and [`Aid::send_interval`]. Delays are rounded up to a whole number of ticks so messages
+    /// are never sent early, but may be sent up to a tick late. A coarser tick means the timer
+    /// wakes up less often. The default value is 1 millisecond.
+    pub timer_tick: Duration,
+    /// The number of shards of the timer, each of which has its own timing wheel, lock and
+    /// thread. Scheduled messages are spread over the shards so that actors scheduling a lot
+    /// of messages at the same time don't contend on the same lock. The default value is 1.
+    pub timer_shards: u16,
+    /// Whether actors are woken on the worker thread that they last ran on, rather than on the
+    /// one with the fewest awake actors, so that busy actors don't keep moving between threads
+    /// and lose the data they had in the CPU cache. An actor still moves when the thread that it
+    /// last ran on is overloaded, see [`ActorSystemConfig::sticky_threshold`]. This can be
+    /// overridden on a per-actor basis with [`ActorBuilder::sticky`]. The default value is false.
+    pub sticky_scheduling: bool,
+    /// How many more awake actors than the worker thread with the fewest the thread that a sticky
+    /// actor last ran on must have for it to be overloaded, in which case the actor is woken on
+    /// the thread with the fewest instead. The default value is 4.
+    pub sticky_threshold: u32,
+    /// The policy that decides which worker thread a woken actor runs on, in which order the
+    /// woken actors run and for how long, see [`SchedulingPolicy`]. The default value is
+    /// [`Scheduling::LeastActors`].
+    pub scheduling: Scheduling,
+    /// The tokio runtime to run the actors on as tasks, instead of on the worker threads of the
+    /// actor system, so that the actors can await tokio I/O and the system doesn't need threads
+    /// of its own to run them. The blocking actors and the closures passed to
+    /// [`Context::spawn_blocking`] run on the blocking threads of the runtime, and pinned actors
+    /// still run on a thread of their own. The `thread_pool_size` and `blocking_pool_size` are
+    /// ignored, as is the order of the woken actors given by the `scheduling` policy. This can't be
+    /// serialized. The default value is `None`.
+    #[cfg(feature = "tokio-runtime")]
+    #[serde(skip)]
+    pub tokio_runtime: Option<tokio::runtime::Handle>,
 }
 
 impl ActorSystemConfig {
@@ -149,6 +274,12 @@ impl ActorSystemConfig {
         self
     }
 
+    /// Return a new config with the changed `blocking_pool_size`.
+    pub fn blocking_pool_size(mut self, value: u16) -> Self {
+        self.blocking_pool_size = value;
+        self
+    }
+
     /// Return a new config with the changed `warn_threshold`.
     pub fn warn_threshold(mut self, value: Duration) -> Self {
         self.warn_threshold = value;
@@ -166,6 +297,44 @@ impl ActorSystemConfig {
         self.thread_wait_time = value;
         self
     }
+
+    /// Return a new config with the changed `timer_tick`.
+    pub fn timer_tick(mut self, value: Duration) -> Self {
+        self.timer_tick = value;
+        self
+    }
+
+    /// Return a new config with the changed `timer_shards`.
+    pub fn timer_shards(mut self, value: u16) -> Self {
+        self.timer_shards = value;
+        self
+    }
+
+    /// Return a new config with the changed `sticky_scheduling`.
+    pub fn sticky_scheduling(mut self, value: bool) -> Self {
+        self.sticky_scheduling = value;
+        self
+    }
+
+    /// Return a new config with the changed `sticky_threshold`.
+    pub fn sticky_threshold(mut self, value: u32) -> Self {
+        self.sticky_threshold = value;
+        self
+    }
+
+    /// Return a new config with the changed `scheduling`.
+    pub fn scheduling(mut self, value: Scheduling) -> Self {
+        self.scheduling = value;
+        self
+    }
+
+    /// Return a new config that runs the actors on the tokio runtime of the `handle`, see
+    /// `tokio_runtime`.
+    #[cfg(feature = "tokio-runtime")]
+    pub fn tokio_runtime(mut self, handle: tokio::runtime::Handle) -> Self {
+        self.tokio_runtime = Some(handle);
+        self
+    }
 }
 
 impl Default for ActorSystemConfig {
@@ -173,16 +342,37 @@ impl Default for ActorSystemConfig {
     fn default() -> ActorSystemConfig {
         ActorSystemConfig {
             thread_pool_size: (num_cpus::get() * 4) as u16,
+            blocking_pool_size: 1,
             warn_threshold: Duration::from_millis(1),
             time_slice: Duration::from_millis(1),
             thread_wait_time: Duration::from_millis(100),
             message_channel_size: 32,
             send_timeout: Duration::from_millis(1),
             start_on_launch: true,
+            timer_tick: Duration::from_millis(1),
+            timer_shards: 1,
+            sticky_scheduling: false,
+            sticky_threshold: 4,
+            scheduling: Scheduling::LeastActors,
+            #[cfg(feature = "tokio-runtime")]
+            tokio_runtime: None,
         }
     }
 }
 
+/// Restores the actor system that the current thread had before [`ActorSystem::enter`] when
+/// dropped.
+#[cfg(feature = "tokio-runtime")]
+pub(crate) struct CurrentGuard(Option<ActorSystem>);
+
+#[cfg(feature = "tokio-runtime")]
+impl Drop for CurrentGuard {
+    fn drop(&mut self) {
+        let previous = self.0.take();
+        ACTOR_SYSTEM.with(|actor_system| *actor_system.borrow_mut() = previous);
+    }
+}
+
 /// Errors produced by the ActorSystem
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub enum SystemError {
@@ -190,6 +380,10 @@ pub enum SystemError {
     /// to register that name for a new actor. The error contains the name that was attempted
     /// to be registered.
     NameAlreadyUsed(String),
+
+    /// An error returned when the user tries to spawn an actor with a name that can't be used,
+    /// such as a name containing a `/`. The error contains the name that was attempted.
+    InvalidName(String),
 }
 
 impl std::fmt::Display for SystemError {
@@ -215,39 +409,6 @@ pub struct RemoteInfo {
     _handle: JoinHandle<()>,
 }
 
-/// Stores a message that will be sent to an actor with a delay.
-struct DelayedMessage {
-    /// A unique identifier for a message.
-    uuid: Uuid,
-    /// The Aid that the message will be sent to.
-    destination: Aid,
-    /// The minimum instant that the message should be sent.
-    instant: Instant,
-    /// The message to sent.
-    message: Message,
-}
-
-impl std::cmp::PartialEq for DelayedMessage {
-    fn eq(&self, other: &Self) -> bool {
-        self.uuid == other.uuid
-    }
-}
-
-impl std::cmp::Eq for DelayedMessage {}
-
-impl std::cmp::PartialOrd for DelayedMessage {
-    fn partial_cmp(&self, other: &DelayedMessage) -> Option<std::cmp::Ordering> {
-        Some(other.instant.cmp(&self.instant)) // Uses an inverted sort.
-    }
-}
-
-impl std::cmp::Ord for DelayedMessage {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.partial_cmp(other)
-            .expect("DelayedMessage::partial_cmp() returned None; can't happen")
-    }
-}
-
 /// Contains the inner data used by the actor system.
 pub(crate) struct ActorSystemData {
     /// Unique version 4 UUID for this actor system.
@@ -270,13 +431,26 @@ pub(crate) struct ActorSystemData {
     /// Holds a map of user assigned names to actor ids set when the actors were spawned. Note
     /// that only actors with an assigned name will be in this map.
     aids_by_name: Arc<DashMap<String, Aid>>,
+    /// Holds a map of the children spawned by each actor where the key is the `aid` of the
+    /// parent. Only actors with children are in this map.
+    children_by_parent: Arc<DashMap<Aid, HashSet<Aid>>>,
     /// Holds a map of monitors where the key is the `aid` of the actor being monitored and
     /// the value is a vector of `aid`s that are monitoring the actor.
     monitoring_by_monitored: Arc<DashMap<Aid, HashSet<Aid>>>,
+    /// Holds the same monitors as `monitoring_by_monitored` but keyed by the `aid` of the
+    /// monitoring actor so that its monitors can be removed when it stops.
+    monitored_by_monitoring: Arc<DashMap<Aid, HashSet<Aid>>>,
+    /// Holds a map of links where the key is the `aid` of an actor and the value is the set of
+    /// `aid`s that are linked to the actor. Every link is stored in both directions.
+    links: Arc<DashMap<Aid, HashSet<Aid>>>,
     /// Holds a map of information objects about links to remote actor systems.
     remotes: Arc<DashMap<Uuid, RemoteInfo>>,
-    /// Holds the messages that have been enqueued for delayed send.
-    delayed_messages: Arc<(Mutex<BinaryHeap<DelayedMessage>>, Condvar)>,
+    /// Holds the messages that have been scheduled for delayed send.
+    timer: Timer,
+    /// The [`Aid`] of the dead letters actor, set when the actor system is started.
+    dead_letters: OnceCell<Aid>,
+    /// Holds the number of dead letters by the reason that they couldn't be delivered.
+    dead_letter_counts: DashMap<DeadLetterReason, usize>,
 }
 
 /// An actor system that contains and manages the actors spawned inside it.
@@ -294,10 +468,13 @@ impl ActorSystem {
     /// on in order to satisfy the requirements of the software they are creating.
     pub fn create(config: ActorSystemConfig) -> ActorSystem {
         let uuid = Uuid::new_v4();
-        let threads = Mutex::new(Vec::with_capacity(config.thread_pool_size as usize));
+        let threads = Mutex::new(Vec::with_capacity(
+            (config.thread_pool_size + config.blocking_pool_size) as usize,
+        ));
         let shutdown_triggered = Arc::new((Mutex::new(false), Condvar::new()));
 
-        let executor = AxiomExecutor::new(shutdown_triggered.clone());
+        let executor = AxiomExecutor::new(config.scheduling.policy());
+        let timer = Timer::new(config.timer_tick, config.timer_shards);
 
         let start_on_launch = config.start_on_launch;
 
@@ -313,9 +490,14 @@ impl ActorSystem {
                 actors_by_aid: Arc::new(DashMap::default()),
                 aids_by_uuid: Arc::new(DashMap::default()),
                 aids_by_name: Arc::new(DashMap::default()),
+                children_by_parent: Arc::new(DashMap::default()),
                 monitoring_by_monitored: Arc::new(DashMap::default()),
+                monitored_by_monitoring: Arc::new(DashMap::default()),
+                links: Arc::new(DashMap::default()),
                 remotes: Arc::new(DashMap::default()),
-                delayed_messages: Arc::new((Mutex::new(BinaryHeap::new()), Condvar::new())),
+                timer,
+                dead_letters: OnceCell::new(),
+                dead_letter_counts: DashMap::default(),
             }),
         };
 
@@ -343,14 +525,19 @@ impl ActorSystem {
             {
                 let mut guard = self.data.threads.lock().unwrap();
 
-                // Start the thread that reads from the `delayed_messages` queue.
-                // FIXME Put in ability to confirm how many of these to start.
-                for _ in 0..1 {
-                    let thread = self.start_send_after_thread();
-                    guard.push(thread);
-                }
+                // Start a thread for each shard of the timer sending the delayed messages.
+                guard.extend(Timer::start(self));
             }
 
+            // Launch the dead letters actor first so that it can receive the dead letters of
+            // all of the other actors.
+            let dead_letters = self
+                .spawn()
+                .name("DeadLetters")
+                .with(DeadLetters::default(), DeadLetters::processor)
+                .unwrap();
+            self.data.dead_letters.set(dead_letters).unwrap();
+
             // Launch the SystemActor and give it the name "System"
             self.spawn()
                 .name("System")
@@ -359,44 +546,6 @@ impl ActorSystem {
         }
     }
 
-    /// Starts a thread that monitors the delayed_messages and sends the messages when their
-    /// delays have elapsed.
-    // FIXME Add a graceful shutdown to this thread and notifications.
-    fn start_send_after_thread(&self) -> JoinHandle<()> {
-        let system = self.clone();
-        let delayed_messages = self.data.delayed_messages.clone();
-        thread::spawn(move || {
-            while !*system.data.shutdown_triggered.0.lock().unwrap() {
-                let (ref mutex, ref condvar) = &*delayed_messages;
-                let mut data = mutex.lock().unwrap();
-                match data.peek() {
-                    None => {
-                        // wait to be notified something is added.
-                        let _ = condvar.wait(data).unwrap();
-                    }
-                    Some(msg) => {
-                        let now = Instant::now();
-                        if now >= msg.instant {
-                            trace!("Sending delayed message");
-                            msg.destination
-                                .send(msg.message.clone())
-                                .unwrap_or_else(|error| {
-                                    warn!(
-                                        "Cannot send scheduled message to {}: Error {:?}",
-                                        msg.destination, error
-                                    );
-                                });
-                            data.pop();
-                        } else {
-                            let duration = msg.instant.duration_since(now);
-                            let _result = condvar.wait_timeout(data, duration).unwrap();
-                        }
-                    }
-                }
-            }
-        })
-    }
-
     /// Returns a reference to the config for this actor system.
     pub fn config(&self) -> &ActorSystemConfig {
         &self.data.config
@@ -469,6 +618,36 @@ impl ActorSystem {
     // FIXME Connectivity management needs a lot of work and testing.
     pub fn disconnect(&self, system_uuid: Uuid) -> Result<(), AidError> {
         self.data.remotes.remove(&system_uuid);
+
+        // The actors on the remote system are unreachable now so whoever monitors them is told
+        // that they are down.
+        let monitored: Vec<Aid> = self
+            .data
+            .monitoring_by_monitored
+            .iter()
+            .filter(|entry| entry.key().system_uuid() == system_uuid)
+            .map(|entry| entry.key().clone())
+            .collect();
+        for aid in monitored {
+            if let Some((_, monitoring)) = self.data.monitoring_by_monitored.remove(&aid) {
+                for m_aid in monitoring.iter() {
+                    remove_from_set(&self.data.monitored_by_monitoring, m_aid, &aid);
+                }
+                self.notify_stopped(&aid, monitoring, StopReason::NodeDown);
+            }
+        }
+
+        // The same goes for the actors that are linked to them.
+        let linked: Vec<Aid> = self
+            .data
+            .links
+            .iter()
+            .filter(|entry| entry.key().system_uuid() == system_uuid)
+            .map(|entry| entry.key().clone())
+            .collect();
+        for aid in linked {
+            self.stop_links(&aid, &StopReason::NodeDown);
+        }
         Ok(())
     }
 
@@ -499,29 +678,54 @@ impl ActorSystem {
                 actor_uuid,
                 system_uuid,
                 message,
+            } => self.deliver_wire_message(system_uuid, actor_uuid, message.clone()),
+            WireMessage::ActorEnvelope {
+                actor_uuid,
+                system_uuid,
+                message,
+                reply_to,
+                priority,
             } => {
-                self.find_aid(&system_uuid, &actor_uuid).map(|aid| {
-                    aid.send(message.clone()).unwrap_or_else(|error| {
-                        warn!("Could not send wire message to {}. Error: {}", aid, error);
-                    })
-                });
+                let message = match reply_to {
+                    Some(reply_to) => message.with_reply_to(reply_to.clone()),
+                    None => message.clone(),
+                };
+                let message = message.with_priority(*priority);
+                self.deliver_wire_message(system_uuid, actor_uuid, message)
             }
             WireMessage::DelayedActorMessage {
                 duration,
                 actor_uuid,
                 system_uuid,
                 message,
-            } => {
-                self.find_aid(&system_uuid, &actor_uuid)
-                    .map(|aid| self.send_after(message.clone(), aid.clone(), *duration))
-                    .expect("Error not handled yet");
-            }
+            } => match self.find_aid(system_uuid, actor_uuid) {
+                Some(aid) => {
+                    self.send_after(message.clone(), aid, *duration);
+                }
+                None => self.dead_letter(
+                    message.clone(),
+                    *actor_uuid,
+                    None,
+                    DeadLetterReason::ActorNotFound,
+                ),
+            },
             WireMessage::Hello { system_actor_aid } => {
                 debug!("{:?} Got Hello from {}", self.data.uuid, system_actor_aid);
             }
         }
     }
 
+    /// Sends a `message` that came from another actor system to the local actor with the given
+    /// UUIDs, or to the dead letters if there is no such actor.
+    fn deliver_wire_message(&self, system_uuid: &Uuid, actor_uuid: &Uuid, message: Message) {
+        match self.find_aid(system_uuid, actor_uuid) {
+            Some(aid) => aid.send(message).unwrap_or_else(|error| {
+                warn!("Could not send wire message to {}. Error: {}", aid, error);
+            }),
+            None => self.dead_letter(message, *actor_uuid, None, DeadLetterReason::ActorNotFound),
+        }
+    }
+
     /// Initializes this actor system to use for the current thread which is necessary if the
     /// user wishes to serialize and deserialize [`Aid`]s.
     ///
@@ -529,18 +733,28 @@ impl ActorSystem {
     /// You must run this exactly once per thread where needed.
     pub fn init_current(&self) {
         ACTOR_SYSTEM.with(|actor_system| {
-            actor_system
-                .set(self.clone())
-                .expect("Unable to set ACTOR_SYSTEM.");
+            let mut actor_system = actor_system.borrow_mut();
+            assert!(actor_system.is_none(), "Unable to set ACTOR_SYSTEM.");
+            *actor_system = Some(self.clone());
         });
     }
 
+    /// Makes this actor system the one of the current thread until the returned guard is dropped,
+    /// for threads that run the actors of other actor systems as well, such as the threads of a
+    /// tokio runtime.
+    #[cfg(feature = "tokio-runtime")]
+    pub(crate) fn enter(&self) -> CurrentGuard {
+        let previous = ACTOR_SYSTEM.with(|actor_system| actor_system.replace(Some(self.clone())));
+        CurrentGuard(previous)
+    }
+
     /// Fetches a clone of a reference to the actor system for the current thread.
     #[inline]
     pub fn current() -> ActorSystem {
         ACTOR_SYSTEM.with(|actor_system| {
             actor_system
-                .get()
+                .borrow()
+                .as_ref()
                 .expect("Thread local ACTOR_SYSTEM not set! See `ActorSystem::init_current()`")
                 .clone()
         })
@@ -557,6 +771,8 @@ impl ActorSystem {
         let (ref mutex, ref condvar) = &*self.data.shutdown_triggered;
         *mutex.lock().unwrap() = true;
         condvar.notify_all();
+        self.data.executor.trigger_shutdown();
+        self.data.timer.shutdown();
     }
 
     /// Awaits the Executor shutting down all Reactors. This is backed by a barrier that Reactors
@@ -657,6 +873,16 @@ impl ActorSystem {
         aids_by_uuid.insert(aid.uuid(), aid.clone());
         self.data.executor.register_actor(stream);
         aid.send(Message::new(SystemMsg::Start)).unwrap(); // Actor was just made
+
+        // Children are added to the tree after they are registered so that a parent that stops
+        // concurrently either sees the child in the tree or the child sees the parent is dead.
+        if let Some(parent) = &actor.parent {
+            insert_into_set(&self.data.children_by_parent, parent, &aid);
+            if !self.is_actor_alive(parent) {
+                debug!("Parent of {} stopped while it was spawned", aid);
+                self.stop_actor(&aid);
+            }
+        }
         Ok(aid)
     }
 
@@ -685,6 +911,19 @@ impl ActorSystem {
             system: self.clone(),
             name: None,
             channel_size: None,
+            parent: None,
+            hooks: Hooks::default(),
+            trap_exits: false,
+            receive_timeout: None,
+            stop_on_receive_timeout: false,
+            blocking: false,
+            pinned: false,
+            sticky: None,
+            priority: Priority::Normal,
+            overflow: MailboxOverflow::Block,
+            priority_mailbox: false,
+            classifier: None,
+            stash_capacity: None,
         }
     }
 
@@ -717,17 +956,33 @@ impl ActorSystem {
     /// This is something that should rarely be called from the outside as it is much better to
     /// send the actor a [`SystemMsg::Stop`] message and allow it to stop gracefully.
     pub fn stop_actor(&self, aid: &Aid) {
-        self.internal_stop_actor(aid, None);
+        self.internal_stop_actor(aid, StopReason::Killed);
     }
 
-    /// Internal implementation of stop_actor, so we have the ability to send an error along with
-    /// the notification of stop.
-    pub(crate) fn internal_stop_actor(&self, aid: &Aid, error: impl Into<Option<StdError>>) {
+    /// Internal implementation of stop_actor, so we have the ability to send the reason along
+    /// with the notification of stop. Any children of the actor are stopped before the actor
+    /// itself.
+    pub(crate) fn internal_stop_actor(&self, aid: &Aid, reason: StopReason) {
+        // Removing the actor first marks it as dead so that no new children can be added to it
+        // while we are stopping the ones it has.
+        let actor = match self.data.actors_by_aid.remove(aid) {
+            Some((_, actor)) => actor,
+            None => {
+                debug!("Actor {} already stopped", aid);
+                return;
+            }
+        };
+
+        if let Some((_, children)) = self.data.children_by_parent.remove(aid) {
+            for child in children {
+                debug!("Stopping {}, child of {}", child, aid);
+                self.internal_stop_actor(&child, StopReason::Shutdown);
+            }
+        }
+
         {
-            let actors_by_aid = &self.data.actors_by_aid;
             let aids_by_uuid = &self.data.aids_by_uuid;
             let aids_by_name = &self.data.aids_by_name;
-            actors_by_aid.remove(aid);
             aids_by_uuid.remove(&aid.uuid());
             if let Some(name_string) = aid.name() {
                 aids_by_name.remove(&name_string);
@@ -735,23 +990,93 @@ impl ActorSystem {
             aid.stop().unwrap();
         }
 
+        // Remove the actor from the children of its parent.
+        if let Some(parent) = &actor.parent {
+            let mut children_by_parent = self.data.children_by_parent.get_raw_mut_from_key(parent);
+            if let Some(children) = children_by_parent.get_mut(parent) {
+                children.remove(aid);
+                if children.is_empty() {
+                    children_by_parent.remove(parent);
+                }
+            }
+        }
+
+        // Stop or notify the actors linked to the actor before the monitors are notified so
+        // that the links are gone by the time a monitoring actor learns about the stop.
+        self.stop_links(aid, &reason);
+
         // Notify all of the actors monitoring the actor that is stopped and remove the
         // actor from the map of monitors.
         if let Some((_, monitoring)) = self.data.monitoring_by_monitored.remove(&aid) {
-            let error = error.into().map(|e| format!("{}", e));
-            for m_aid in monitoring {
-                let value = SystemMsg::Stopped {
-                    aid: aid.clone(),
-                    error: error.clone(),
-                };
-                m_aid.send(Message::new(value)).unwrap_or_else(|error| {
-                    error!(
-                        "Could not send 'Stopped' to monitoring actor {}: Error: {:?}",
-                        m_aid, error
-                    );
-                });
+            for m_aid in monitoring.iter() {
+                remove_from_set(&self.data.monitored_by_monitoring, m_aid, aid);
+            }
+            self.notify_stopped(aid, monitoring, reason.clone());
+        }
+
+        // Remove the monitors the actor had on other actors as it can't receive notifications.
+        if let Some((_, monitored)) = self.data.monitored_by_monitoring.remove(aid) {
+            for m_aid in monitored.iter() {
+                remove_from_set(&self.data.monitoring_by_monitored, m_aid, aid);
             }
         }
+
+        // Wake the actor so that it runs its stop hooks if it was sleeping.
+        self.data.executor.wake(aid.clone());
+    }
+
+    /// Sends a [`SystemMsg::Stopped`] with the given reason to each of the monitoring actors.
+    fn notify_stopped(
+        &self,
+        aid: &Aid,
+        monitoring: impl IntoIterator<Item = Aid>,
+        reason: StopReason,
+    ) {
+        for m_aid in monitoring {
+            let value = SystemMsg::Stopped {
+                aid: aid.clone(),
+                reason: reason.clone(),
+            };
+            m_aid.send(Message::new(value)).unwrap_or_else(|error| {
+                error!(
+                    "Could not send 'Stopped' to monitoring actor {}: Error: {:?}",
+                    m_aid, error
+                );
+            });
+        }
+    }
+
+    /// Removes all of the links of the `stopped` actor and lets each of the actors that were
+    /// linked to it know that it stopped with the given reason.
+    fn stop_links(&self, stopped: &Aid, reason: &StopReason) {
+        if let Some((_, linked)) = self.data.links.remove(stopped) {
+            for l_aid in linked {
+                // Whoever removes the other side of the link is responsible for propagating the
+                // stop so that it is propagated exactly once.
+                if remove_from_set(&self.data.links, &l_aid, stopped) {
+                    self.exit_linked(&l_aid, stopped, reason);
+                }
+            }
+        }
+    }
+
+    /// Lets the `linked` actor know that the `stopped` actor stopped. If the `linked` actor traps
+    /// exits it is sent a [`SystemMsg::Stopped`], otherwise it is stopped as well if the
+    /// `stopped` actor stopped abnormally.
+    fn exit_linked(&self, linked: &Aid, stopped: &Aid, reason: &StopReason) {
+        let trap_exits = match self.data.actors_by_aid.get(linked) {
+            Some(actor) => actor.trap_exits,
+            // The linked actor is already stopping so there is nothing to do.
+            None if linked.system_uuid() == self.uuid() => return,
+            // Remote actors can only be told about the stop.
+            None => true,
+        };
+        if trap_exits {
+            self.notify_stopped(stopped, Some(linked.clone()), reason.clone());
+        } else if !reason.is_normal() {
+            debug!("Stopping {}, linked to {}", linked, stopped);
+            self.internal_stop_actor(linked, StopReason::Linked(stopped.clone()));
+        }
     }
 
     /// Checks to see if the actor with the given [`Aid`] is alive within this actor system.
@@ -768,9 +1093,12 @@ impl ActorSystem {
     }
 
     /// Look up an [`Aid`] by the user assigned name of the actor and either returns the
-    /// located [`Aid`] in a [`Option::Some`] or [`Option::None`] if not found.
+    /// located [`Aid`] in a [`Option::Some`] or [`Option::None`] if not found. The names of
+    /// children are paths scoped under the names of their parents, such as `orders/worker-3`,
+    /// and may be given with a leading `/`, such as `/orders/worker-3`.
     pub fn find_aid_by_name(&self, name: &str) -> Option<Aid> {
         let aids_by_name = &self.data.aids_by_name;
+        let name = name.strip_prefix('/').unwrap_or(name);
         aids_by_name.get(&name.to_string()).map(|aid| aid.clone())
     }
 
@@ -791,16 +1119,219 @@ impl ActorSystem {
         self.find_aid_by_name(&"System").unwrap()
     }
 
-    /// Adds a monitor so that `monitoring` will be informed if `monitored` stops.
+    /// Returns the [`Aid`] to the "DeadLetters" actor for this actor system. This actor is sent
+    /// a [`DeadLetter`] for every message that couldn't be delivered.
+    pub fn dead_letters_aid(&self) -> Aid {
+        self.data.dead_letters.get().unwrap().clone()
+    }
+
+    /// Subscribes the actor to the dead letters of the actor system, so that it is sent every
+    /// [`DeadLetter`] until it stops or is unsubscribed.
+    ///
+    /// # Examples
+    /// ```
+    /// use axiom::prelude::*;
+    ///
+    /// let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+    ///
+    /// async fn handler(_: (), _: Context, message: Message) -> ActorResult<()> {
+    ///     if let Some(letter) = message.content_as::<DeadLetter>() {
+    ///         println!("{:?} couldn't be delivered: {:?}", letter.recipient, letter.reason);
+    ///     }
+    ///     Ok(Status::done(()))
+    /// }
+    ///
+    /// let subscriber = system.spawn().with((), handler).unwrap();
+    /// system.subscribe_dead_letters(&subscriber);
+    ///
+    /// system.trigger_and_await_shutdown(None);
+    /// ```
+    pub fn subscribe_dead_letters(&self, subscriber: &Aid) {
+        let message = Message::new(DeadLettersMessage::Subscribe(subscriber.clone()));
+        let dead_letters = self.dead_letters_aid();
+        dead_letters.send(message).unwrap_or_else(|error| {
+            error!(
+                "Could not subscribe {} to dead letters: {:?}",
+                subscriber, error
+            )
+        });
+    }
+
+    /// Unsubscribes the actor from the dead letters of the actor system.
+    pub fn unsubscribe_dead_letters(&self, subscriber: &Aid) {
+        let message = Message::new(DeadLettersMessage::Unsubscribe(subscriber.clone()));
+        let dead_letters = self.dead_letters_aid();
+        dead_letters.send(message).unwrap_or_else(|error| {
+            error!(
+                "Could not unsubscribe {} from dead letters: {:?}",
+                subscriber, error
+            )
+        });
+    }
+
+    /// Returns the number of messages that couldn't be delivered for the given reason since the
+    /// actor system was created.
+    pub fn dead_letter_count(&self, reason: DeadLetterReason) -> usize {
+        self.data
+            .dead_letter_counts
+            .get(&reason)
+            .map_or(0, |count| *count)
+    }
+
+    /// Returns the total number of messages that couldn't be delivered since the actor system
+    /// was created.
+    pub fn dead_letters_total(&self) -> usize {
+        self.data
+            .dead_letter_counts
+            .iter()
+            .map(|count| *count.value())
+            .sum()
+    }
+
+    /// Returns a snapshot of the metrics of each of the reactor threads that run the actors,
+    /// including the blocking ones, such as how many actors the reactors stole from each other.
+    /// Reactors that run out of work steal woken actors from busy reactors, so a high number of
+    /// steals means that the work is unevenly spread over the reactors. The threads of pinned
+    /// actors are left out.
+    pub fn reactor_metrics(&self) -> Vec<ReactorMetrics> {
+        self.data.executor.reactor_metrics()
+    }
+
+    /// Records a message that couldn't be delivered to the actor with the `recipient_uuid` and
+    /// sends it to the dead letters actor.
+    pub(crate) fn dead_letter(
+        &self,
+        message: Message,
+        recipient_uuid: Uuid,
+        recipient: Option<Aid>,
+        reason: DeadLetterReason,
+    ) {
+        let dead_letters = match self.data.dead_letters.get() {
+            Some(dead_letters) => dead_letters,
+            None => return,
+        };
+        // A letter to the dead letters actor itself can only happen once it has stopped so it is
+        // dropped rather than sent in circles.
+        if recipient_uuid == dead_letters.uuid() {
+            return;
+        }
+        {
+            let mut counts = self.data.dead_letter_counts.get_raw_mut_from_key(&reason);
+            *counts.entry(reason).or_insert(0) += 1;
+        }
+        let letter = DeadLetter {
+            message,
+            recipient_uuid,
+            recipient,
+            reason,
+        };
+        dead_letters
+            .send(Message::new(letter))
+            .unwrap_or_else(|error| debug!("Dropping dead letter. Error: {:?}", error));
+    }
+
+    /// Adds a monitor so that `monitoring` will be informed if `monitored` stops. If the
+    /// `monitored` actor is local and has already stopped, `monitoring` is informed immediately.
+    /// The monitor is removed once `monitored` stops or when `monitoring` itself stops.
     pub fn monitor(&self, monitoring: &Aid, monitored: &Aid) {
-        let mut monitoring_by_monitored = self
-            .data
-            .monitoring_by_monitored
-            .get_raw_mut_from_key(&monitored);
-        let monitoring_vec = monitoring_by_monitored
-            .entry(monitored.clone())
-            .or_insert(HashSet::new());
-        monitoring_vec.insert(monitoring.clone());
+        insert_into_set(&self.data.monitoring_by_monitored, monitored, monitoring);
+        insert_into_set(&self.data.monitored_by_monitoring, monitoring, monitored);
+
+        // The monitoring actor may have stopped while we added the monitor, in which case it
+        // would never be removed.
+        if monitoring.is_stopped() {
+            self.demonitor(monitoring, monitored);
+            return;
+        }
+
+        // The actor may have stopped before the monitor was added, in which case nobody would
+        // ever send the notification. Whoever removes the monitor from the map sends it so that
+        // the notification is sent exactly once, even if we race with the actor stopping.
+        if monitored.system_uuid() == self.uuid()
+            && !self.is_actor_alive(monitored)
+            && remove_from_set(&self.data.monitoring_by_monitored, monitored, monitoring)
+        {
+            remove_from_set(&self.data.monitored_by_monitoring, monitoring, monitored);
+            self.notify_stopped(monitored, Some(monitoring.clone()), StopReason::NotAlive);
+        }
+    }
+
+    /// Removes the monitor that `monitoring` has on `monitored` if there is one. Once this
+    /// returns `monitoring` won't be informed when `monitored` stops, although a notification
+    /// sent before the monitor was removed may still be in its channel.
+    pub fn demonitor(&self, monitoring: &Aid, monitored: &Aid) {
+        remove_from_set(&self.data.monitoring_by_monitored, monitored, monitoring);
+        remove_from_set(&self.data.monitored_by_monitoring, monitoring, monitored);
+    }
+
+    /// Links the two actors together so that if either of them stops abnormally, meaning the
+    /// [`StopReason`] isn't normal, the other is stopped as well with [`StopReason::Linked`].
+    /// Actors spawned with [`ActorBuilder::trap_exits`] are never stopped because of a link,
+    /// instead they are sent a [`SystemMsg::Stopped`] whenever an actor linked to them stops,
+    /// regardless of the reason. Linking to a local actor that has already stopped behaves as
+    /// if that actor stopped with [`StopReason::NotAlive`].
+    ///
+    /// Links to actors on a remote actor system are only triggered when the remote actor system
+    /// is disconnected, in which case the reason is [`StopReason::NodeDown`].
+    ///
+    /// # Examples
+    /// ```
+    /// use axiom::prelude::*;
+    ///
+    /// let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+    ///
+    /// async fn handler(_: (), _: Context, message: Message) -> ActorResult<()> {
+    ///     match message.content_as::<i32>() {
+    ///         Some(_) => Err(SystemError::InvalidName("".to_string()).into()),
+    ///         None => Ok(Status::done(())),
+    ///     }
+    /// }
+    ///
+    /// let aid1 = system.spawn().with((), handler).unwrap();
+    /// let aid2 = system.spawn().with((), handler).unwrap();
+    /// system.link(&aid1, &aid2);
+    ///
+    /// // When `aid1` fails, `aid2` is stopped as well.
+    /// aid1.send_new(11).unwrap();
+    /// # while system.is_actor_alive(&aid2) { std::thread::yield_now(); }
+    ///
+    /// system.trigger_and_await_shutdown(None);
+    /// ```
+    pub fn link(&self, aid1: &Aid, aid2: &Aid) {
+        insert_into_set(&self.data.links, aid1, aid2);
+        insert_into_set(&self.data.links, aid2, aid1);
+
+        // Like with monitors, whoever removes the link of an actor that already stopped
+        // propagates the stop.
+        for (stopped, linked) in &[(aid1, aid2), (aid2, aid1)] {
+            if stopped.system_uuid() == self.uuid() && !self.is_actor_alive(stopped) {
+                remove_from_set(&self.data.links, stopped, linked);
+                if remove_from_set(&self.data.links, linked, stopped) {
+                    self.exit_linked(linked, stopped, &StopReason::NotAlive);
+                }
+            }
+        }
+    }
+
+    /// Removes the link between the two actors if there is one.
+    pub fn unlink(&self, aid1: &Aid, aid2: &Aid) {
+        remove_from_set(&self.data.links, aid1, aid2);
+        remove_from_set(&self.data.links, aid2, aid1);
+    }
+
+    /// Registers the reply endpoint of an [`Aid::ask`] so that it can be found by its uuid
+    /// when a reply comes in from a remote actor system.
+    pub(crate) fn register_reply_endpoint(&self, endpoint: &Aid) {
+        self.data
+            .aids_by_uuid
+            .insert(endpoint.uuid(), endpoint.clone());
+    }
+
+    /// Removes the reply endpoint of an [`Aid::ask`] along with its monitor of the `asked`
+    /// actor once the endpoint has received a message.
+    pub(crate) fn deregister_reply_endpoint(&self, endpoint: &Aid, asked: &Aid) {
+        self.data.aids_by_uuid.remove(&endpoint.uuid());
+        self.demonitor(endpoint, asked);
     }
 
     /// Asynchronously send a message to the system actors on all connected actor systems.
@@ -820,18 +1351,44 @@ impl ActorSystem {
     /// That this method makes a best attempt at sending the message on time but the message may
     /// not be sent on exactly the delay passed. However, the message will never be sent before
     /// the given delay.
-    pub(crate) fn send_after(&self, message: Message, destination: Aid, delay: Duration) {
-        let instant = Instant::now().checked_add(delay).unwrap();
-        let entry = DelayedMessage {
-            uuid: Uuid::new_v4(),
-            destination,
-            instant,
-            message,
-        };
-        let (ref mutex, ref condvar) = &*self.data.delayed_messages;
-        let mut data = mutex.lock().unwrap();
-        data.push(entry);
-        condvar.notify_all();
+    pub(crate) fn send_after(
+        &self,
+        message: Message,
+        destination: Aid,
+        delay: Duration,
+    ) -> ScheduleHandle {
+        self.data.timer.schedule(message, destination, delay, None)
+    }
+
+    /// Schedules a `message` to be sent to the `destination` [`Aid`] every `interval` until
+    /// the schedule is cancelled or the `destination` stops. The messages are sent at a fixed
+    /// rate, with the same best attempt at timing as [`ActorSystem::send_after`].
+    pub(crate) fn send_interval(
+        &self,
+        message: Message,
+        destination: Aid,
+        interval: Duration,
+    ) -> ScheduleHandle {
+        assert!(interval > Duration::from_millis(0));
+        self.data
+            .timer
+            .schedule(message, destination, interval, Some(interval))
+    }
+
+    /// Creates a future that completes after the `duration`, driven by the same timer as the
+    /// delayed messages.
+    pub(crate) fn sleep(&self, duration: Duration) -> Sleep {
+        self.data.timer.sleep(duration)
+    }
+
+    /// Determines if messages can still be delivered to the actor, meaning that either the actor
+    /// is local and hasn't stopped or its actor system is still connected.
+    fn is_reachable(&self, aid: &Aid) -> bool {
+        if aid.system_uuid() == self.uuid() {
+            !aid.is_stopped()
+        } else {
+            self.data.remotes.contains_key(&aid.system_uuid())
+        }
     }
 
     #[cfg(test)]
@@ -840,6 +1397,30 @@ impl ActorSystem {
     }
 }
 
+/// Inserts the `value` into the set stored under the `key` in the `map`, creating the set if
+/// needed.
+fn insert_into_set(map: &DashMap<Aid, HashSet<Aid>>, key: &Aid, value: &Aid) {
+    let mut map = map.get_raw_mut_from_key(key);
+    let set = map.entry(key.clone()).or_default();
+    set.insert(value.clone());
+}
+
+/// Removes the `value` from the set stored under the `key` in the `map`, removing the set if it
+/// becomes empty. Returns `true` if the value was in the set.
+fn remove_from_set(map: &DashMap<Aid, HashSet<Aid>>, key: &Aid, value: &Aid) -> bool {
+    let mut map = map.get_raw_mut_from_key(key);
+    let removed = match map.get_mut(key) {
+        Some(set) => set.remove(value),
+        None => false,
+    };
+    if let Some(set) = map.get(key) {
+        if set.is_empty() {
+            map.remove(key);
+        }
+    }
+    removed
+}
+
 impl fmt::Debug for ActorSystem {
     fn fmt(&self, formatter: &'_ mut fmt::Formatter) -> fmt::Result {
         write!(
@@ -882,6 +1463,32 @@ mod tests {
         h2.join().unwrap();
     }
 
+    /// Tests that a config missing some of the fields, such as one written before those fields
+    /// were added, deserializes with the default values for the missing fields.
+    #[test]
+    fn test_config_missing_fields() {
+        let json = r#"{
+            "message_channel_size": 64,
+            "send_timeout": { "secs": 0, "nanos": 2000000 },
+            "thread_pool_size": 2,
+            "warn_threshold": { "secs": 0, "nanos": 1000000 },
+            "time_slice": { "secs": 0, "nanos": 1000000 },
+            "thread_wait_time": { "secs": 0, "nanos": 100000000 },
+            "start_on_launch": false
+        }"#;
+        let config: ActorSystemConfig = serde_json::from_str(json).unwrap();
+        let default = ActorSystemConfig::default();
+        assert_eq!(64, config.message_channel_size);
+        assert_eq!(Duration::from_millis(2), config.send_timeout);
+        assert_eq!(2, config.thread_pool_size);
+        assert!(!config.start_on_launch);
+        assert_eq!(default.blocking_pool_size, config.blocking_pool_size);
+        assert_eq!(default.timer_tick, config.timer_tick);
+        assert_eq!(default.timer_shards, config.timer_shards);
+        assert_eq!(default.sticky_scheduling, config.sticky_scheduling);
+        assert_eq!(default.sticky_threshold, config.sticky_threshold);
+    }
+
     /// Test that verifies that the actor system shutdown mechanisms that wait for a specific
     /// timeout work properly.
     #[test]
@@ -1019,6 +1626,76 @@ mod tests {
         system.trigger_and_await_shutdown(None);
     }
 
+    /// Tests that a message scheduled with send_after is never sent once it is cancelled.
+    #[test]
+    fn test_send_after_cancel() {
+        init_test_log();
+
+        let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+        let aid = system.spawn().with((), simple_handler).unwrap();
+        await_received(&aid, 1, 1000).unwrap();
+
+        let handle = aid
+            .send_after(Message::new(11), Duration::from_millis(10))
+            .unwrap();
+        assert!(!handle.is_cancelled());
+        handle.cancel();
+        assert!(handle.is_cancelled());
+        sleep(30);
+        assert_eq!(1, aid.received().unwrap());
+        assert_eq!(0, system.data.timer.len());
+
+        system.trigger_and_await_shutdown(None);
+    }
+
+    /// Tests that messages scheduled with send_interval are sent repeatedly until the schedule
+    /// is cancelled.
+    #[test]
+    fn test_send_interval() {
+        init_test_log();
+
+        let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+        let aid = system.spawn().with((), simple_handler).unwrap();
+        await_received(&aid, 1, 1000).unwrap();
+
+        let handle = aid.send_new_interval(11, Duration::from_millis(5)).unwrap();
+        await_received(&aid, 4, 1000).unwrap();
+        handle.cancel();
+        sleep(10);
+        let received = aid.received().unwrap();
+        sleep(20);
+        assert_eq!(received, aid.received().unwrap());
+        assert_eq!(0, system.data.timer.len());
+
+        system.trigger_and_await_shutdown(None);
+    }
+
+    /// Tests that messages scheduled with send_interval stop being sent once the actor stops,
+    /// without ending up in the dead letters.
+    #[test]
+    fn test_send_interval_stops_with_actor() {
+        init_test_log();
+
+        let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+        let aid = system.spawn().with((), simple_handler).unwrap();
+        await_received(&aid, 1, 1000).unwrap();
+
+        let handle = aid.send_new_interval(11, Duration::from_millis(5)).unwrap();
+        await_received(&aid, 3, 1000).unwrap();
+        system.stop_actor(&aid);
+        sleep(20);
+        assert!(!handle.is_cancelled());
+        assert_eq!(0, system.data.timer.len());
+        assert_eq!(0, system.dead_letters_total());
+        assert_eq!(
+            Err(AidError::ActorAlreadyStopped),
+            aid.send_new_interval(11, Duration::from_millis(5))
+                .map(|_| ())
+        );
+
+        system.trigger_and_await_shutdown(None);
+    }
+
     /// Tests that if we execute two send_after calls, one for a longer duration than the
     /// second, that the message will be sent for the second one before the first one enqueued
     /// and that the second one will still arrive properly.
@@ -1115,11 +1792,13 @@ mod tests {
         ) -> ActorResult<(Aid, AssertCollect)> {
             if let Some(msg) = message.content_as::<SystemMsg>() {
                 match &*msg {
-                    SystemMsg::Stopped { aid, error } => {
+                    SystemMsg::Stopped { aid, reason } => {
                         state
                             .1
                             .assert(Aid::ptr_eq(&state.0, aid), "Pointers are not equal!");
-                        state.1.assert(error.is_none(), "Actor was errored!");
+                        state
+                            .1
+                            .assert(*reason == StopReason::Killed, "Actor wasn't killed!");
                         Ok(Status::done(state))
                     }
                     SystemMsg::Start => Ok(Status::done(state)),
@@ -1186,12 +1865,11 @@ mod tests {
             .with(aid.clone(), move |state: Aid, _: Context, msg: Message| {
                 if let Some(msg) = msg.content_as::<SystemMsg>() {
                     match &*msg {
-                        SystemMsg::Stopped { aid, error } => {
+                        SystemMsg::Stopped { aid, reason } => {
                             t.assert(*aid == state, "Aid is not expected Aid");
-                            t.assert(error.is_some(), "Expected error");
                             t.assert(
-                                error.as_ref().unwrap() == "I panicked",
-                                "Error message does not match",
+                                *reason == StopReason::Panic("I panicked".to_string()),
+                                "Stop reason does not match",
                             );
                             future::ok(Status::stop(state))
                         }
@@ -1249,6 +1927,553 @@ mod tests {
         system.trigger_and_await_shutdown(None);
     }
 
+    /// A processor that spawns a child named `child` on start which itself has no children.
+    async fn spawn_child(_: (), context: Context, message: Message) -> ActorResult<()> {
+        if let Some(msg) = message.content_as::<SystemMsg>() {
+            if let SystemMsg::Start = &*msg {
+                context.spawn().name("child").with((), simple_handler)?;
+            }
+        }
+        Ok(Status::done(()))
+    }
+
+    /// A processor that spawns a child named `child` on start which spawns a child of its own.
+    async fn spawn_grandchild(_: (), context: Context, message: Message) -> ActorResult<()> {
+        if let Some(msg) = message.content_as::<SystemMsg>() {
+            if let SystemMsg::Start = &*msg {
+                context.spawn().name("child").with((), spawn_child)?;
+            }
+        }
+        Ok(Status::done(()))
+    }
+
+    /// Waits for the `child` to be added to the children of the `parent`, which happens right
+    /// after the name of the child is registered.
+    fn await_child(system: &ActorSystem, parent: &Aid, child: &Aid) {
+        let start = Instant::now();
+        loop {
+            if let Some(children) = system.data.children_by_parent.get(parent) {
+                if children.contains(child) {
+                    break;
+                }
+            }
+            assert!(start.elapsed() < Duration::from_millis(1000));
+            sleep(1);
+        }
+    }
+
+    /// Tests that actors can spawn children that are registered under the name of their parent
+    /// and that stopping a parent stops all of its descendants.
+    #[test]
+    fn test_child_actors() {
+        init_test_log();
+
+        let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+        let root = system
+            .spawn()
+            .name("root")
+            .with((), spawn_grandchild)
+            .unwrap();
+        let child = await_named(&system, "root/child", 1000).unwrap();
+        let grandchild = await_named(&system, "/root/child/child", 1000).unwrap();
+        assert_eq!(Some("root/child/child".to_string()), grandchild.name());
+        await_child(&system, &root, &child);
+        await_child(&system, &child, &grandchild);
+
+        {
+            let children_by_parent = &system.data.children_by_parent;
+            assert!(children_by_parent.get(&root).unwrap().contains(&child));
+            assert!(children_by_parent
+                .get(&child)
+                .unwrap()
+                .contains(&grandchild));
+        }
+
+        // Stopping the root stops all of the descendants and cleans up the tree.
+        system.stop_actor(&root);
+        assert!(!system.is_actor_alive(&root));
+        assert!(!system.is_actor_alive(&child));
+        assert!(!system.is_actor_alive(&grandchild));
+        assert_eq!(None, system.find_aid_by_name("root/child"));
+        assert_eq!(None, system.find_aid_by_name("root/child/child"));
+        assert_eq!(0, system.data.children_by_parent.len());
+
+        system.trigger_and_await_shutdown(None);
+    }
+
+    /// Tests that a stopped child is removed from its parent and that children of unnamed
+    /// parents are scoped under the uuid of the parent.
+    #[test]
+    fn test_child_stopped_before_parent() {
+        init_test_log();
+
+        let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+        let parent = system.spawn().with((), spawn_child).unwrap();
+        let name = format!("{}/child", parent.uuid());
+        let child = await_named(&system, &name, 1000).unwrap();
+        await_child(&system, &parent, &child);
+
+        system.stop_actor(&child);
+        assert!(system.is_actor_alive(&parent));
+        assert!(system.data.children_by_parent.get(&parent).is_none());
+
+        system.trigger_and_await_shutdown(None);
+    }
+
+    /// The reasons collected by the actor spawned with [`spawn_reason_collector`].
+    type Reasons = Arc<Mutex<Vec<(Aid, StopReason)>>>;
+
+    /// Spawns an actor that records the [`StopReason`] of every `Stopped` message it receives.
+    fn spawn_reason_collector(system: &ActorSystem) -> (Aid, Reasons) {
+        let reasons = Arc::new(Mutex::new(Vec::new()));
+        let r = reasons.clone();
+        let aid = system
+            .spawn()
+            .with((), move |_: (), _: Context, message: Message| {
+                let r = r.clone();
+                async move {
+                    if let Some(msg) = message.content_as::<SystemMsg>() {
+                        if let SystemMsg::Stopped { aid, reason } = &*msg {
+                            r.lock().unwrap().push((aid.clone(), reason.clone()));
+                        }
+                    }
+                    Ok(Status::done(()))
+                }
+            })
+            .unwrap();
+        (aid, reasons)
+    }
+
+    /// Waits for the collector to receive the `Stopped` message for the `aid` and returns
+    /// the reason that the actor stopped.
+    fn await_reason(reasons: &Reasons, aid: &Aid) -> StopReason {
+        let start = Instant::now();
+        loop {
+            let found = reasons
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|(a, _)| a == aid)
+                .cloned();
+            if let Some((_, reason)) = found {
+                return reason;
+            } else if Instant::elapsed(&start) > Duration::from_millis(1000) {
+                panic!("Timed out waiting for the stop reason of {}", aid);
+            }
+            sleep(1);
+        }
+    }
+
+    /// Tests that monitors are told why the monitored actor stopped.
+    #[test]
+    fn test_stop_reasons() {
+        init_test_log();
+
+        let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+        let (collector, reasons) = spawn_reason_collector(&system);
+
+        let normal = system.spawn().with((), simple_handler).unwrap();
+        system.monitor(&collector, &normal);
+        normal.send_new(SystemMsg::Stop).unwrap();
+        assert_eq!(StopReason::Normal, await_reason(&reasons, &normal));
+
+        let errored = system
+            .spawn()
+            .with((), |_: (), _: Context, message: Message| async move {
+                match message.content_as::<i32>() {
+                    Some(_) => Err(SystemError::NameAlreadyUsed("Foo".to_string()).into()),
+                    None => Ok(Status::done(())),
+                }
+            })
+            .unwrap();
+        system.monitor(&collector, &errored);
+        errored.send_new(11).unwrap();
+        let expected = StopReason::Error("NameAlreadyUsed(\"Foo\")".to_string());
+        assert_eq!(expected, await_reason(&reasons, &errored));
+
+        let parent = system.spawn().with((), spawn_child).unwrap();
+        let name = format!("{}/child", parent.uuid());
+        let child = await_named(&system, &name, 1000).unwrap();
+        await_child(&system, &parent, &child);
+        system.monitor(&collector, &parent);
+        system.monitor(&collector, &child);
+        system.stop_actor(&parent);
+        assert_eq!(StopReason::Killed, await_reason(&reasons, &parent));
+        assert_eq!(StopReason::Shutdown, await_reason(&reasons, &child));
+        assert!(!StopReason::Killed.is_normal());
+        assert!(StopReason::Shutdown.is_normal());
+
+        let (late, late_reasons) = spawn_reason_collector(&system);
+        system.monitor(&late, &normal);
+        assert_eq!(StopReason::NotAlive, await_reason(&late_reasons, &normal));
+
+        system.trigger_and_await_shutdown(None);
+    }
+
+    /// Tests that an actor configured to stop on its receive timeout stops once it's idle, with
+    /// a normal reason.
+    #[test]
+    fn test_stop_on_receive_timeout() {
+        init_test_log();
+
+        let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+        let (collector, reasons) = spawn_reason_collector(&system);
+        let aid = system
+            .spawn()
+            .receive_timeout(Duration::from_millis(20))
+            .stop_on_receive_timeout(true)
+            .with((), simple_handler)
+            .unwrap();
+        system.monitor(&collector, &aid);
+
+        let reason = await_reason(&reasons, &aid);
+        assert_eq!(StopReason::ReceiveTimeout, reason);
+        assert!(reason.is_normal());
+        assert_eq!(0, system.dead_letters_total());
+
+        system.trigger_and_await_shutdown(None);
+    }
+
+    /// Tests that monitors of actors on a remote actor system are told that the actors are
+    /// down when the remote actor system is disconnected.
+    #[test]
+    fn test_stop_reason_node_down() {
+        init_test_log();
+        let (system1, system2) = start_and_connect_two_systems();
+
+        system1.init_current();
+        let aid = system1.spawn().with((), simple_handler).unwrap();
+        let serialized = bincode::serialize(&aid).unwrap();
+
+        let system1_uuid = system1.uuid();
+        let handle = thread::spawn(move || {
+            system2.init_current();
+            let remote: Aid = bincode::deserialize(&serialized).unwrap();
+            let (collector, reasons) = spawn_reason_collector(&system2);
+            system2.monitor(&collector, &remote);
+            system2.disconnect(system1_uuid).unwrap();
+            assert_eq!(StopReason::NodeDown, await_reason(&reasons, &remote));
+            assert_eq!(0, system2.data.monitoring_by_monitored.len());
+            system2
+        });
+        let system2 = handle.join().unwrap();
+
+        system1.trigger_shutdown();
+        system2.trigger_shutdown();
+        await_two_system_shutdown(system1, system2);
+    }
+
+    /// Tests that monitors can be removed and that the monitors of an actor are removed when
+    /// the actor stops.
+    #[test]
+    fn test_demonitor() {
+        init_test_log();
+
+        let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+        let (collector, reasons) = spawn_reason_collector(&system);
+        let monitored = system.spawn().with((), simple_handler).unwrap();
+
+        system.monitor(&collector, &monitored);
+        system.demonitor(&collector, &monitored);
+        assert_eq!(0, system.data.monitoring_by_monitored.len());
+        assert_eq!(0, system.data.monitored_by_monitoring.len());
+        system.stop_actor(&monitored);
+        sleep(10);
+        assert!(reasons.lock().unwrap().is_empty());
+
+        let monitored = system.spawn().with((), simple_handler).unwrap();
+        system.monitor(&collector, &monitored);
+        system.stop_actor(&collector);
+        assert_eq!(0, system.data.monitoring_by_monitored.len());
+        assert_eq!(0, system.data.monitored_by_monitoring.len());
+
+        system.trigger_and_await_shutdown(None);
+    }
+
+    /// An actor that fails when it receives an `i32`.
+    async fn fail_on_i32(_: (), _: Context, message: Message) -> ActorResult<()> {
+        match message.content_as::<i32>() {
+            Some(_) => Err(SystemError::InvalidName("".to_string()).into()),
+            None => Ok(Status::done(())),
+        }
+    }
+
+    /// Tests that actors that are linked are stopped when the other stops abnormally but not
+    /// when it stops normally.
+    #[test]
+    fn test_links() {
+        init_test_log();
+
+        let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+        let (collector, reasons) = spawn_reason_collector(&system);
+
+        let failing = system.spawn().with((), fail_on_i32).unwrap();
+        let linked = system.spawn().with((), simple_handler).unwrap();
+        system.monitor(&collector, &linked);
+        system.link(&failing, &linked);
+        failing.send_new(11).unwrap();
+        let expected = StopReason::Linked(failing.clone());
+        assert_eq!(expected, await_reason(&reasons, &linked));
+        assert_eq!(0, system.data.links.len());
+
+        let normal = system.spawn().with((), simple_handler).unwrap();
+        let linked = system.spawn().with((), simple_handler).unwrap();
+        system.link(&normal, &linked);
+        system.monitor(&collector, &normal);
+        normal.send_new(SystemMsg::Stop).unwrap();
+        assert_eq!(StopReason::Normal, await_reason(&reasons, &normal));
+        assert!(system.is_actor_alive(&linked));
+        assert_eq!(0, system.data.links.len());
+
+        let failing = system.spawn().with((), fail_on_i32).unwrap();
+        system.link(&failing, &linked);
+        system.unlink(&linked, &failing);
+        system.monitor(&collector, &failing);
+        failing.send_new(11).unwrap();
+        await_reason(&reasons, &failing);
+        assert!(system.is_actor_alive(&linked));
+
+        // Linking to an actor that already stopped stops the other actor.
+        system.monitor(&collector, &linked);
+        system.link(&linked, &failing);
+        let expected = StopReason::Linked(failing.clone());
+        assert_eq!(expected, await_reason(&reasons, &linked));
+        assert_eq!(0, system.data.links.len());
+
+        system.trigger_and_await_shutdown(None);
+    }
+
+    /// Tests that an actor that traps exits receives the stops of linked actors as messages
+    /// instead of being stopped.
+    #[test]
+    fn test_links_trap_exits() {
+        init_test_log();
+
+        let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+        let reasons = Arc::new(Mutex::new(Vec::new()));
+        let r = reasons.clone();
+        let trapping = system
+            .spawn()
+            .trap_exits(true)
+            .with((), move |_: (), _: Context, message: Message| {
+                let r = r.clone();
+                async move {
+                    if let Some(msg) = message.content_as::<SystemMsg>() {
+                        if let SystemMsg::Stopped { aid, reason } = &*msg {
+                            r.lock().unwrap().push((aid.clone(), reason.clone()));
+                        }
+                    }
+                    Ok(Status::done(()))
+                }
+            })
+            .unwrap();
+
+        let failing = system.spawn().with((), fail_on_i32).unwrap();
+        let normal = system.spawn().with((), simple_handler).unwrap();
+        system.link(&trapping, &failing);
+        system.link(&trapping, &normal);
+        failing.send_new(11).unwrap();
+        normal.send_new(SystemMsg::Stop).unwrap();
+
+        let expected = StopReason::Error("InvalidName(\"\")".to_string());
+        assert_eq!(expected, await_reason(&reasons, &failing));
+        assert_eq!(StopReason::Normal, await_reason(&reasons, &normal));
+        assert!(system.is_actor_alive(&trapping));
+        assert_eq!(0, system.data.links.len());
+
+        system.trigger_and_await_shutdown(None);
+    }
+
+    /// The letters collected by the actor spawned with [`spawn_dead_letter_subscriber`].
+    type Letters = Arc<Mutex<Vec<DeadLetter>>>;
+
+    /// Spawns an actor that subscribes to the dead letters and collects them.
+    fn spawn_dead_letter_subscriber(system: &ActorSystem) -> Letters {
+        let letters = Arc::new(Mutex::new(Vec::new()));
+        let l = letters.clone();
+        let aid = system
+            .spawn()
+            .with((), move |_: (), _: Context, message: Message| {
+                let l = l.clone();
+                async move {
+                    if let Some(letter) = message.content_as::<DeadLetter>() {
+                        l.lock().unwrap().push((*letter).clone());
+                    }
+                    Ok(Status::done(()))
+                }
+            })
+            .unwrap();
+        system.subscribe_dead_letters(&aid);
+        // The dead letters actor has processed `Start` and the subscription after this.
+        await_received(&system.dead_letters_aid(), 2, 1000).unwrap();
+        letters
+    }
+
+    /// Waits for the subscriber to have collected the given number of letters.
+    fn await_letters(letters: &Letters, count: usize) {
+        let start = Instant::now();
+        while letters.lock().unwrap().len() < count {
+            if Instant::elapsed(&start) > Duration::from_millis(1000) {
+                panic!("Timed out waiting for {} dead letters", count);
+            }
+            sleep(1);
+        }
+    }
+
+    /// Tests that messages sent to stopped actors end up in the dead letters, including delayed
+    /// messages to actors that stopped before the message was due.
+    #[test]
+    fn test_dead_letters_actor_stopped() {
+        init_test_log();
+
+        let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+        let letters = spawn_dead_letter_subscriber(&system);
+
+        let aid = system.spawn().with((), simple_handler).unwrap();
+        aid.send_after(Message::new(7), Duration::from_millis(10))
+            .unwrap();
+        system.stop_actor(&aid);
+        assert_eq!(Err(AidError::ActorAlreadyStopped), aid.send_new(11));
+        assert_eq!(1, system.dead_letter_count(DeadLetterReason::ActorStopped));
+
+        await_letters(&letters, 2);
+        assert_eq!(2, system.dead_letter_count(DeadLetterReason::ActorStopped));
+        assert_eq!(2, system.dead_letters_total());
+        let letters = letters.lock().unwrap();
+        let values: Vec<i32> = letters
+            .iter()
+            .map(|letter| *letter.message.content_as::<i32>().unwrap())
+            .collect();
+        assert_eq!(vec![11, 7], values);
+        for letter in letters.iter() {
+            assert_eq!(Some(aid.clone()), letter.recipient);
+            assert_eq!(aid.uuid(), letter.recipient_uuid);
+            assert_eq!(DeadLetterReason::ActorStopped, letter.reason);
+        }
+        drop(letters);
+
+        system.trigger_and_await_shutdown(None);
+    }
+
+    /// Tests that wire messages for actors that can't be found end up in the dead letters.
+    #[test]
+    fn test_dead_letters_actor_not_found() {
+        init_test_log();
+
+        let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+        let letters = spawn_dead_letter_subscriber(&system);
+
+        let actor_uuid = Uuid::new_v4();
+        let wire_message = WireMessage::ActorMessage {
+            actor_uuid,
+            system_uuid: system.uuid(),
+            message: Message::new(11),
+        };
+        system.process_wire_message(&Uuid::new_v4(), &wire_message);
+        let wire_message = WireMessage::DelayedActorMessage {
+            duration: Duration::from_millis(10),
+            actor_uuid,
+            system_uuid: system.uuid(),
+            message: Message::new(11),
+        };
+        system.process_wire_message(&Uuid::new_v4(), &wire_message);
+        assert_eq!(2, system.dead_letter_count(DeadLetterReason::ActorNotFound));
+
+        await_letters(&letters, 2);
+        for letter in letters.lock().unwrap().iter() {
+            assert_eq!(None, letter.recipient);
+            assert_eq!(actor_uuid, letter.recipient_uuid);
+            assert_eq!(DeadLetterReason::ActorNotFound, letter.reason);
+        }
+
+        system.trigger_and_await_shutdown(None);
+    }
+
+    /// Tests that the [`Aid`] to reply to a message and its priority aren't serialized with the
+    /// message, so that its serialized form doesn't change, but that they're carried to the other
+    /// actor system by a `WireMessage::ActorEnvelope` and attached to the message again there.
+    #[test]
+    fn test_wire_message_envelope() {
+        init_test_log();
+
+        let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+        system.init_current();
+        let replier = system
+            .spawn()
+            .with((), |_: (), _: Context, message: Message| async move {
+                // Only a message that kept its priority is replied to.
+                if message.priority() == MessagePriority::High {
+                    message.reply_to().unwrap().send_new(22)?;
+                }
+                Ok(Status::done(()))
+            })
+            .unwrap();
+        let asker = system.spawn().with((), simple_handler).unwrap();
+
+        let message = Message::new(11)
+            .with_reply_to(asker.clone())
+            .with_priority(MessagePriority::High);
+        let serialized = bincode::serialize(&message).unwrap();
+        assert_eq!(bincode::serialize(&Message::new(11)).unwrap(), serialized);
+        let message: Message = bincode::deserialize(&serialized).unwrap();
+        assert_eq!(None, message.reply_to());
+        assert_eq!(MessagePriority::Normal, message.priority());
+
+        let wire_message = WireMessage::ActorEnvelope {
+            actor_uuid: replier.uuid(),
+            system_uuid: system.uuid(),
+            message: Message::new(11),
+            reply_to: Some(asker.clone()),
+            priority: MessagePriority::High,
+        };
+        let serialized = bincode::serialize(&wire_message).unwrap();
+        let wire_message: WireMessage = bincode::deserialize(&serialized).unwrap();
+        system.process_wire_message(&Uuid::new_v4(), &wire_message);
+        await_received(&asker, 2, 1000).unwrap();
+
+        system.trigger_and_await_shutdown(None);
+    }
+
+    /// Tests that messages an actor doesn't handle end up in the dead letters.
+    #[test]
+    fn test_dead_letters_unhandled() {
+        init_test_log();
+
+        struct Ignorer;
+
+        impl Dispatch for Ignorer {
+            async fn dispatch(self, context: Context, message: Message) -> ActorResult<Self> {
+                self.unhandled(context, message)
+            }
+        }
+
+        let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+        let letters = spawn_dead_letter_subscriber(&system);
+
+        let aid = system.spawn().with(Ignorer, Ignorer::dispatch).unwrap();
+        aid.send_new(11).unwrap();
+        await_letters(&letters, 1);
+        assert_eq!(1, system.dead_letter_count(DeadLetterReason::Unhandled));
+        assert_eq!(1, system.dead_letters_total());
+        let letter = letters.lock().unwrap()[0].clone();
+        assert_eq!(Some(aid), letter.recipient);
+        assert_eq!(DeadLetterReason::Unhandled, letter.reason);
+
+        system.trigger_and_await_shutdown(None);
+    }
+
+    /// Tests that names containing the separator used for the names of children are refused.
+    #[test]
+    fn test_invalid_names() {
+        init_test_log();
+
+        let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+        let result = system.spawn().name("a/b").with((), simple_handler);
+        assert_eq!(Err(SystemError::InvalidName("a/b".to_string())), result);
+
+        system.trigger_and_await_shutdown(None);
+    }
+
     /// Tests that remote actors can send and receive messages between each other.
     #[test]
     fn test_remote_actors() {
@@ -1319,6 +2544,45 @@ mod tests {
         tracker.collect();
     }
 
+    /// Tests that actors on remote systems can be asked for a reply and that the reply
+    /// endpoints are removed from the system once they get their reply.
+    #[test]
+    fn test_remote_ask() {
+        init_test_log();
+        let (system1, system2) = start_and_connect_two_systems();
+
+        system1.init_current();
+        let aid = system1
+            .spawn()
+            .with((), |_: (), _: Context, message: Message| async move {
+                if let Some(value) = message.content_as::<i32>() {
+                    message.reply_to().unwrap().send_new(*value * 2)?;
+                }
+                Ok(Status::done(()))
+            })
+            .unwrap();
+        let serialized = bincode::serialize(&aid).unwrap();
+
+        let handle = thread::spawn(move || {
+            system2.init_current();
+            let remote: Aid = bincode::deserialize(&serialized).unwrap();
+            let timeout = Duration::from_millis(1000);
+            let reply = remote.ask_blocking::<i32>(Message::new(11), timeout);
+            assert_eq!(22, *reply.unwrap());
+
+            // Only the system and dead letters actors should be left once the endpoint got its
+            // reply.
+            assert_eq!(2, system2.data.aids_by_uuid.len());
+            assert_eq!(0, system2.data.monitoring_by_monitored.len());
+            system2
+        });
+        let system2 = handle.join().unwrap();
+
+        system1.trigger_shutdown();
+        system2.trigger_shutdown();
+        await_two_system_shutdown(system1, system2);
+    }
+
     /// Tests the ability to find an aid on a remote system by name using a `SystemActor`. This
     /// also serves as a test for cross system actor communication as well as testing broadcast
     /// to multiple system actors in the cluster.
@@ -0,0 +1,116 @@
+//! Implements the dead letters actor that collects the messages which couldn't be delivered to
+//! their recipients, such as messages sent to stopped actors or to actors with a full mailbox.
+//! Each undeliverable message is wrapped in a [`DeadLetter`] envelope that records the intended
+//! recipient and the [`DeadLetterReason`], logged, and forwarded to the actors subscribed with
+//! [`ActorSystem::subscribe_dead_letters`].
+
+use crate::prelude::*;
+use log::{debug, error, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+/// An envelope holding a message that couldn't be delivered to its recipient. The envelopes are
+/// sent to the dead letters actor of the actor system, which forwards them to its subscribers.
+/// See [`ActorSystem::subscribe_dead_letters`] for more information.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DeadLetter {
+    /// The message that couldn't be delivered.
+    pub message: Message,
+    /// The UUID of the actor that the message was sent to.
+    pub recipient_uuid: Uuid,
+    /// The [`Aid`] of the actor that the message was sent to. This is only `None` when a remote
+    /// actor system sent a message to an actor this actor system doesn't know about.
+    pub recipient: Option<Aid>,
+    /// The reason that the message couldn't be delivered.
+    pub reason: DeadLetterReason,
+}
+
+impl std::fmt::Debug for DeadLetter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DeadLetter")
+            .field("recipient_uuid", &self.recipient_uuid)
+            .field("recipient", &self.recipient)
+            .field("reason", &self.reason)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The reason that a message ended up as a [`DeadLetter`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum DeadLetterReason {
+    /// The message was sent to an actor that has already stopped.
+    ActorStopped,
+    /// The message was sent by a remote actor system to an actor that couldn't be found.
+    ActorNotFound,
+    /// The message was received by an actor that didn't handle it. See
+    /// [`Dispatch::unhandled`].
+    Unhandled,
+    /// The message was sent to an actor with a full mailbox, which was spawned with
+    /// [`MailboxOverflow::DeadLetter`].
+    MailboxFull,
+}
+
+/// Messages that are sent to the dead letters actor to manage its subscribers.
+#[derive(Serialize, Deserialize, Debug)]
+pub(crate) enum DeadLettersMessage {
+    /// Adds the actor to the subscribers.
+    Subscribe(Aid),
+    /// Removes the actor from the subscribers.
+    Unsubscribe(Aid),
+}
+
+/// The dead letters actor is a unique actor on the system registered with the name
+/// "DeadLetters". It logs the [`DeadLetter`]s it receives and forwards them to its subscribers.
+#[derive(Default)]
+pub(crate) struct DeadLetters {
+    /// The actors that are sent each [`DeadLetter`].
+    subscribers: HashSet<Aid>,
+}
+
+impl DeadLetters {
+    /// The processor function for the dead letters actor.
+    pub(crate) async fn processor(
+        mut self,
+        context: Context,
+        message: Message,
+    ) -> ActorResult<Self> {
+        if let Some(letter) = message.content_as::<DeadLetter>() {
+            debug!(
+                "Dead letter for {}: {:?}",
+                letter
+                    .recipient
+                    .as_ref()
+                    .map_or_else(|| letter.recipient_uuid.to_string(), |aid| aid.to_string()),
+                letter.reason
+            );
+            for subscriber in self.subscribers.iter() {
+                subscriber.send(message.clone()).unwrap_or_else(|error| {
+                    error!(
+                        "Could not send dead letter to subscriber {}. Error: {:?}",
+                        subscriber, error
+                    )
+                });
+            }
+        } else if let Some(msg) = message.content_as::<DeadLettersMessage>() {
+            match &*msg {
+                DeadLettersMessage::Subscribe(aid) => {
+                    // Subscribers are removed once they stop.
+                    context.system.monitor(&context.aid, aid);
+                    self.subscribers.insert(aid.clone());
+                }
+                DeadLettersMessage::Unsubscribe(aid) => {
+                    context.system.demonitor(&context.aid, aid);
+                    self.subscribers.remove(aid);
+                }
+            }
+        } else if let Some(msg) = message.content_as::<SystemMsg>() {
+            if let SystemMsg::Stopped { aid, .. } = &*msg {
+                self.subscribers.remove(aid);
+            }
+        } else {
+            warn!("[{}] Unhandled message received.", context.aid);
+        }
+        Ok(Status::done(self))
+    }
+}
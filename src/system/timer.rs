@@ -0,0 +1,805 @@
+//! Implements the timer that holds the messages scheduled with [`Aid::send_after`] and
+//! [`Aid::send_interval`] until they are due, as well as the [`Sleep`] and [`Timeout`] futures
+//! returned by [`Context::sleep`] and [`Context::timeout`].
+//!
+//! The timer is split into a configurable number of shards, each with its own hierarchical
+//! timing wheel, lock and thread, so that actors scheduling messages on many threads don't all
+//! contend on the same lock. Each wheel has [`LEVELS`] levels of [`SLOTS`] slots where a slot at
+//! level `n` covers `SLOTS^n` ticks. Scheduling and cancelling a message takes constant time no
+//! matter how many messages are pending, and messages move down to the lower levels as their
+//! deadline approaches. Cancelled messages are dropped when their slot expires or, when a lot
+//! of them pile up, in batches when scheduling.
+
+use crate::prelude::*;
+use log::{trace, warn};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::task::{Poll, Waker};
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// The number of bits of a tick that index the slots of a single level.
+const SLOT_BITS: u32 = 6;
+
+/// The number of slots in each level of a wheel.
+const SLOTS: usize = 1 << SLOT_BITS;
+
+/// The number of levels in each wheel.
+const LEVELS: usize = 6;
+
+/// The furthest ahead of the current tick that an entry can be placed in a wheel. Entries that
+/// are due later than this are placed at the top level and placed again when their slot expires.
+const MAX_TICKS: u64 = (1 << (SLOT_BITS * LEVELS as u32)) - 1;
+
+/// The number of cancellations in a shard after which the cancelled entries are dropped from
+/// the wheel, as long as at least half of the entries in the wheel could have been cancelled.
+/// Cancelled entries are otherwise only dropped once their slot expires.
+const COMPACT_THRESHOLD: usize = 1024;
+
+/// A handle to a message scheduled with [`Aid::send_after`] or [`Aid::send_interval`] that
+/// can be used to cancel the schedule. Dropping the handle doesn't cancel the schedule.
+#[derive(Clone, Debug)]
+pub struct ScheduleHandle {
+    /// Set when the schedule is cancelled, shared with the scheduled [`TimerEntry`].
+    cancelled: Arc<AtomicBool>,
+    /// The number of cancellations in the shard holding the [`TimerEntry`].
+    cancellations: Arc<AtomicUsize>,
+}
+
+impl ScheduleHandle {
+    /// Cancels the schedule so that no more messages are sent. Messages that were already sent
+    /// are not affected and cancelling a schedule that already completed does nothing.
+    pub fn cancel(&self) {
+        if !self.cancelled.swap(true, Ordering::AcqRel) {
+            self.cancellations.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Determines if the schedule was cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Acquire)
+    }
+}
+
+/// A future that completes once its duration has elapsed, created with [`Context::sleep`].
+/// The future is woken by the timer of the actor system, so it works inside of the processors
+/// of actors without any other runtime. Dropping the future cancels it.
+#[derive(Debug)]
+pub struct Sleep {
+    /// The state shared with the timer.
+    state: Arc<SleepState>,
+    /// The handle used to cancel the sleep when it is dropped early.
+    handle: ScheduleHandle,
+}
+
+/// The state of a [`Sleep`] that is shared with the timer.
+#[derive(Debug, Default)]
+struct SleepState {
+    /// Set by the timer once the sleep has elapsed.
+    elapsed: AtomicBool,
+    /// The waker of the task that last polled the sleep.
+    waker: Mutex<Option<Waker>>,
+}
+
+impl SleepState {
+    /// Marks the sleep as elapsed and wakes the task awaiting it.
+    fn wake(&self) {
+        self.elapsed.store(true, Ordering::Release);
+        if let Some(waker) = self.waker.lock().unwrap().take() {
+            waker.wake();
+        }
+    }
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<()> {
+        // The waker is stored under the lock so that the timer can't wake in between.
+        let mut waker = self.state.waker.lock().unwrap();
+        if self.state.elapsed.load(Ordering::Acquire) {
+            return Poll::Ready(());
+        }
+        match waker.as_ref() {
+            Some(waker) if waker.will_wake(cx.waker()) => (),
+            _ => *waker = Some(cx.waker().clone()),
+        }
+        Poll::Pending
+    }
+}
+
+impl Drop for Sleep {
+    fn drop(&mut self) {
+        if !self.state.elapsed.load(Ordering::Acquire) {
+            self.handle.cancel();
+        }
+    }
+}
+
+/// A future that completes with the output of the inner future, or with a [`TimeoutError`] if
+/// the inner future doesn't complete in time. Created with [`Context::timeout`]. Dropping the
+/// future drops the inner future and cancels the timeout.
+#[derive(Debug)]
+pub struct Timeout<F: Future> {
+    /// The future that is given the time to complete.
+    future: Pin<Box<F>>,
+    /// The sleep that completes when the time is up.
+    sleep: Sleep,
+}
+
+impl<F: Future> Timeout<F> {
+    pub(crate) fn new(future: F, sleep: Sleep) -> Timeout<F> {
+        Timeout {
+            future: Box::pin(future),
+            sleep,
+        }
+    }
+}
+
+impl<F: Future> Future for Timeout<F> {
+    type Output = Result<F::Output, TimeoutError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        if let Poll::Ready(output) = self.future.as_mut().poll(cx) {
+            return Poll::Ready(Ok(output));
+        }
+        Pin::new(&mut self.sleep)
+            .poll(cx)
+            .map(|()| Err(TimeoutError))
+    }
+}
+
+/// The error returned by a [`Timeout`] when the inner future didn't complete in time.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TimeoutError;
+
+impl std::fmt::Display for TimeoutError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl std::error::Error for TimeoutError {}
+
+/// What the timer does when an entry is due.
+enum Target {
+    /// Sends the message to the actor with the [`Aid`].
+    Message { destination: Aid, message: Message },
+    /// Wakes a [`Sleep`].
+    Wake(Arc<SleepState>),
+}
+
+/// Stores what the timer does when a delay has elapsed.
+struct TimerEntry {
+    /// What to do when the entry is due.
+    target: Target,
+    /// The minimum instant that the message should be sent.
+    instant: Instant,
+    /// The first tick at or after the `instant`, which is when the message is due.
+    deadline: u64,
+    /// The interval at which the message is sent again, if it is sent periodically.
+    interval: Option<Duration>,
+    /// Set when the schedule is cancelled through its [`ScheduleHandle`].
+    cancelled: Arc<AtomicBool>,
+}
+
+/// A single level of a [`Wheel`].
+struct Level {
+    /// The entries in each of the slots of the level.
+    slots: Vec<Vec<TimerEntry>>,
+    /// A bit for each slot that is set when the slot holds entries.
+    occupied: u64,
+}
+
+/// A hierarchical timing wheel holding entries by the tick that they are due.
+struct Wheel {
+    /// The tick that the wheel has been advanced to.
+    elapsed: u64,
+    /// The levels of the wheel, from the finest to the coarsest.
+    levels: Vec<Level>,
+    /// The entries that are due and waiting to be sent.
+    due: Vec<TimerEntry>,
+    /// The number of entries held in the wheel, including the due entries.
+    len: usize,
+    /// The tick that the shard thread is waiting for, `u64::MAX` if it is waiting for entries
+    /// and 0 if it isn't waiting at all. Used to only wake the thread when needed.
+    wakeup: u64,
+}
+
+impl Wheel {
+    fn new() -> Wheel {
+        let levels = (0..LEVELS)
+            .map(|_| Level {
+                slots: (0..SLOTS).map(|_| Vec::new()).collect(),
+                occupied: 0,
+            })
+            .collect();
+        Wheel {
+            elapsed: 0,
+            levels,
+            due: Vec::new(),
+            len: 0,
+            wakeup: 0,
+        }
+    }
+
+    /// Adds the entry to the wheel.
+    fn insert(&mut self, entry: TimerEntry) {
+        self.len += 1;
+        self.place(entry);
+    }
+
+    /// Places the entry into the slot for its deadline or with the due entries if the wheel has
+    /// already advanced past the deadline.
+    fn place(&mut self, entry: TimerEntry) {
+        if entry.deadline <= self.elapsed {
+            self.due.push(entry);
+            return;
+        }
+        let deadline = entry.deadline.min(self.elapsed + MAX_TICKS);
+        let index = level_for(self.elapsed, deadline);
+        let slot = ((deadline >> (SLOT_BITS * index as u32)) % SLOTS as u64) as usize;
+        let level = &mut self.levels[index];
+        level.slots[slot].push(entry);
+        level.occupied |= 1 << slot;
+    }
+
+    /// Finds the next slot to expire, returning its level, slot and the tick it expires at.
+    /// The lowest occupied level always holds the next slot to expire because each level only
+    /// holds entries that are due after the current slot of the level above.
+    fn next_expiration(&self) -> Option<(usize, usize, u64)> {
+        let (index, level) = self
+            .levels
+            .iter()
+            .enumerate()
+            .find(|(_, level)| level.occupied != 0)?;
+        let slot_ticks = 1u64 << (SLOT_BITS * index as u32);
+        let level_ticks = slot_ticks << SLOT_BITS;
+        let current = ((self.elapsed / slot_ticks) % SLOTS as u64) as u32;
+        let slot =
+            (level.occupied.rotate_right(current).trailing_zeros() + current) as usize % SLOTS;
+        let mut tick = self.elapsed - self.elapsed % level_ticks + slot as u64 * slot_ticks;
+        // Only the top level can wrap around to slots before the current one.
+        if tick <= self.elapsed {
+            tick += level_ticks;
+        }
+        Some((index, slot, tick))
+    }
+
+    /// Advances the wheel to the `now` tick, moving the entries that are due to the due entries
+    /// and the other entries of each expired slot down to the lower levels. Cancelled entries
+    /// are dropped along the way.
+    fn advance(&mut self, now: u64) {
+        while let Some((index, slot, tick)) = self.next_expiration() {
+            if tick > now {
+                break;
+            }
+            self.elapsed = tick;
+            let level = &mut self.levels[index];
+            level.occupied &= !(1 << slot);
+            for entry in std::mem::take(&mut level.slots[slot]) {
+                if entry.cancelled.load(Ordering::Acquire) {
+                    self.len -= 1;
+                } else {
+                    self.place(entry);
+                }
+            }
+        }
+        self.elapsed = self.elapsed.max(now);
+    }
+
+    /// Drops the cancelled entries from all of the slots of the wheel.
+    fn compact(&mut self) {
+        for level in self.levels.iter_mut() {
+            for (slot, entries) in level.slots.iter_mut().enumerate() {
+                let before = entries.len();
+                entries.retain(|entry| !entry.cancelled.load(Ordering::Acquire));
+                self.len -= before - entries.len();
+                if entries.is_empty() {
+                    level.occupied &= !(1 << slot);
+                }
+            }
+        }
+    }
+
+    /// Removes the due entries from the wheel.
+    fn take_due(&mut self) -> Vec<TimerEntry> {
+        self.len -= self.due.len();
+        std::mem::take(&mut self.due)
+    }
+}
+
+/// Determines the level of a wheel at the `elapsed` tick that an entry due at the `deadline`
+/// tick belongs in, which is based on the highest bit that differs between the two.
+fn level_for(elapsed: u64, deadline: u64) -> usize {
+    let masked = (elapsed ^ deadline) | (SLOTS as u64 - 1);
+    let significant = 63 - masked.leading_zeros();
+    ((significant / SLOT_BITS) as usize).min(LEVELS - 1)
+}
+
+/// A shard of the timer with its own wheel, which is served by its own thread.
+struct Shard {
+    /// The wheel holding the entries of this shard.
+    wheel: Mutex<Wheel>,
+    /// Used to wake the thread of the shard when an entry is added that is due before the
+    /// thread would otherwise wake up.
+    condvar: Condvar,
+    /// The number of entries of the shard that were cancelled since the wheel was compacted.
+    cancellations: Arc<AtomicUsize>,
+}
+
+/// The timer of an actor system which sends the scheduled messages once they are due.
+pub(crate) struct Timer {
+    /// The duration of a single tick of the wheels.
+    tick: Duration,
+    /// The instant of tick 0.
+    origin: Instant,
+    /// The shards of the timer. Entries are spread over the shards in turn.
+    shards: Vec<Shard>,
+    /// The shard that the next entry will be added to.
+    next_shard: AtomicUsize,
+    /// Set when the timer is shut down, which ends the threads of the shards.
+    shutdown: AtomicBool,
+}
+
+impl Timer {
+    /// Creates a timer that advances every `tick` and has `shards` wheels.
+    pub(crate) fn new(tick: Duration, shards: u16) -> Timer {
+        assert!(
+            tick > Duration::from_millis(0),
+            "The timer tick must be > 0"
+        );
+        assert!(shards > 0, "The timer must have at least one shard");
+        Timer {
+            tick,
+            origin: Instant::now(),
+            shards: (0..shards)
+                .map(|_| Shard {
+                    wheel: Mutex::new(Wheel::new()),
+                    condvar: Condvar::new(),
+                    cancellations: Arc::new(AtomicUsize::new(0)),
+                })
+                .collect(),
+            next_shard: AtomicUsize::new(0),
+            shutdown: AtomicBool::new(false),
+        }
+    }
+
+    /// Starts a thread for each of the shards of the timer of the `system`.
+    pub(crate) fn start(system: &ActorSystem) -> Vec<JoinHandle<()>> {
+        (0..system.data.timer.shards.len())
+            .map(|index| {
+                let system = system.clone();
+                thread::spawn(move || system.data.timer.run_shard(&system, index))
+            })
+            .collect()
+    }
+
+    /// Shuts down the timer, ending the threads of the shards. Messages that weren't sent yet
+    /// are dropped.
+    pub(crate) fn shutdown(&self) {
+        self.shutdown.store(true, Ordering::Release);
+        for shard in self.shards.iter() {
+            // Taking the lock makes sure the thread is either waiting or will see the flag.
+            let _wheel = shard.wheel.lock().unwrap();
+            shard.condvar.notify_all();
+        }
+    }
+
+    /// Schedules the `message` to be sent to the `destination` after the `delay` and then
+    /// every `interval`, if any, returning the handle to cancel it.
+    pub(crate) fn schedule(
+        &self,
+        message: Message,
+        destination: Aid,
+        delay: Duration,
+        interval: Option<Duration>,
+    ) -> ScheduleHandle {
+        let target = Target::Message {
+            destination,
+            message,
+        };
+        self.insert(target, delay, interval)
+    }
+
+    /// Creates a future that completes after the `delay`.
+    pub(crate) fn sleep(&self, delay: Duration) -> Sleep {
+        let state = Arc::new(SleepState::default());
+        let handle = self.insert(Target::Wake(state.clone()), delay, None);
+        Sleep { state, handle }
+    }
+
+    /// Adds an entry for the `target` to one of the shards.
+    fn insert(
+        &self,
+        target: Target,
+        delay: Duration,
+        interval: Option<Duration>,
+    ) -> ScheduleHandle {
+        let instant = Instant::now().checked_add(delay).unwrap();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let entry = TimerEntry {
+            target,
+            instant,
+            deadline: self.tick_after(instant),
+            interval,
+            cancelled: cancelled.clone(),
+        };
+
+        let index = self.next_shard.fetch_add(1, Ordering::Relaxed) % self.shards.len();
+        let shard = &self.shards[index];
+        let mut wheel = shard.wheel.lock().unwrap();
+        let wake = entry.deadline < wheel.wakeup;
+        wheel.insert(entry);
+        if wake {
+            shard.condvar.notify_one();
+        }
+        // Keeps entries that are cancelled long before they are due from piling up.
+        if shard.cancellations.load(Ordering::Relaxed) > COMPACT_THRESHOLD.max(wheel.len / 2) {
+            shard.cancellations.store(0, Ordering::Relaxed);
+            wheel.compact();
+        }
+        ScheduleHandle {
+            cancelled,
+            cancellations: shard.cancellations.clone(),
+        }
+    }
+
+    /// Returns the number of messages held by the timer, including cancelled messages that
+    /// haven't been dropped yet.
+    #[cfg(test)]
+    pub(crate) fn len(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| shard.wheel.lock().unwrap().len)
+            .sum()
+    }
+
+    /// The loop run by the thread of the shard at `index`, which sends the entries that are due
+    /// and waits for the next slot to expire.
+    fn run_shard(&self, system: &ActorSystem, index: usize) {
+        let shard = &self.shards[index];
+        let mut wheel = shard.wheel.lock().unwrap();
+        while !self.shutdown.load(Ordering::Acquire) {
+            wheel.advance(self.current_tick());
+            let due = wheel.take_due();
+            if due.is_empty() {
+                wheel.wakeup = match wheel.next_expiration() {
+                    Some((_, _, tick)) => tick,
+                    None => u64::MAX,
+                };
+                wheel = if wheel.wakeup == u64::MAX {
+                    shard.condvar.wait(wheel).unwrap()
+                } else {
+                    let timeout = self
+                        .instant_of(wheel.wakeup)
+                        .saturating_duration_since(Instant::now());
+                    shard.condvar.wait_timeout(wheel, timeout).unwrap().0
+                };
+                wheel.wakeup = 0;
+                continue;
+            }
+
+            // The messages are sent without holding the lock so that scheduling isn't blocked.
+            drop(wheel);
+            let periodic: Vec<TimerEntry> = due
+                .into_iter()
+                .filter_map(|entry| self.send(system, entry))
+                .collect();
+            wheel = shard.wheel.lock().unwrap();
+            for entry in periodic {
+                wheel.insert(entry);
+            }
+        }
+    }
+
+    /// Sends the message of the `entry`, or wakes its sleep, unless it was cancelled, returning
+    /// the entry again if the message is periodic and should be scheduled for its next send.
+    fn send(&self, system: &ActorSystem, mut entry: TimerEntry) -> Option<TimerEntry> {
+        if entry.cancelled.load(Ordering::Acquire) {
+            trace!("Dropping cancelled delayed message");
+            return None;
+        }
+        let (destination, message) = match &entry.target {
+            Target::Message {
+                destination,
+                message,
+            } => (destination, message),
+            Target::Wake(state) => {
+                state.wake();
+                return None;
+            }
+        };
+        // Periodic messages end quietly once the destination is gone.
+        if entry.interval.is_some() && !system.is_reachable(destination) {
+            trace!("Ending periodic message to {}", destination);
+            return None;
+        }
+        trace!("Sending delayed message");
+        destination.send(message.clone()).unwrap_or_else(|error| {
+            warn!(
+                "Cannot send scheduled message to {}: Error {:?}",
+                destination, error
+            );
+        });
+        // Periodic messages are sent at a fixed rate so the next instant is based on when this
+        // one was due rather than when it was sent.
+        let interval = entry.interval?;
+        entry.instant += interval;
+        entry.deadline = self.tick_after(entry.instant);
+        Some(entry)
+    }
+
+    /// Returns the tick that has most recently started.
+    fn current_tick(&self) -> u64 {
+        (self.origin.elapsed().as_nanos() / self.tick.as_nanos()) as u64
+    }
+
+    /// Returns the first tick that starts at or after the `instant`, so that messages are never
+    /// sent before their instant.
+    fn tick_after(&self, instant: Instant) -> u64 {
+        let nanos = instant.saturating_duration_since(self.origin).as_nanos();
+        let tick = self.tick.as_nanos();
+        nanos.div_ceil(tick) as u64
+    }
+
+    /// Returns the instant that the `tick` starts, saturating far in the future.
+    fn instant_of(&self, tick: u64) -> Instant {
+        let nanos = self.tick.as_nanos() * tick as u128;
+        let offset = Duration::new(
+            (nanos / 1_000_000_000).min(u64::MAX as u128) as u64,
+            (nanos % 1_000_000_000) as u32,
+        );
+        self.origin
+            .checked_add(offset)
+            .unwrap_or_else(|| Instant::now() + Duration::from_secs(86_400))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    /// Creates a target that only wakes a [`SleepState`], so that the wheel and timer can be
+    /// tested without an actor system to send messages to.
+    fn target() -> Target {
+        Target::Wake(Arc::new(SleepState::default()))
+    }
+
+    /// Creates an entry that is due at the `deadline` tick.
+    fn entry(deadline: u64) -> (TimerEntry, ScheduleHandle) {
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let entry = TimerEntry {
+            target: target(),
+            instant: Instant::now(),
+            deadline,
+            interval: None,
+            cancelled: cancelled.clone(),
+        };
+        let cancellations = Arc::new(AtomicUsize::new(0));
+        let handle = ScheduleHandle {
+            cancelled,
+            cancellations,
+        };
+        (entry, handle)
+    }
+
+    /// Advances the wheel tick by tick until the entries run out, returning the tick at which
+    /// each entry became due.
+    fn drain(wheel: &mut Wheel) -> Vec<(u64, u64)> {
+        let mut results = Vec::new();
+        while wheel.len > 0 {
+            let (_, _, tick) = wheel.next_expiration().unwrap();
+            wheel.advance(tick);
+            for entry in wheel.take_due() {
+                results.push((wheel.elapsed, entry.deadline));
+            }
+        }
+        results
+    }
+
+    /// Tests that entries on every level of the wheel, and beyond, become due exactly at their
+    /// deadline and in order.
+    #[test]
+    fn test_wheel_deadlines() {
+        let deadlines = vec![
+            MAX_TICKS * 3 + 17,
+            1,
+            63,
+            64,
+            65,
+            4095,
+            4096,
+            300_000,
+            MAX_TICKS,
+            MAX_TICKS + 1,
+            1 << 40,
+        ];
+        let mut wheel = Wheel::new();
+        for deadline in deadlines.iter() {
+            wheel.insert(entry(*deadline).0);
+        }
+        assert_eq!(deadlines.len(), wheel.len);
+
+        let mut expected: Vec<(u64, u64)> = deadlines.iter().map(|d| (*d, *d)).collect();
+        expected.sort();
+        assert_eq!(expected, drain(&mut wheel));
+        assert!(wheel.next_expiration().is_none());
+    }
+
+    /// Tests that entries added after the wheel advanced a long way, with a clock that jumps
+    /// past several slots at once, are still due at the right time and never early.
+    #[test]
+    fn test_wheel_advance_jumps() {
+        let mut wheel = Wheel::new();
+        wheel.advance(123_456);
+        for offset in [1, 100, 5_000, 70_000].iter() {
+            wheel.insert(entry(123_456 + offset).0);
+        }
+        wheel.advance(123_456 + 99);
+        assert_eq!(1, wheel.take_due().len());
+        wheel.advance(123_456 + 4_999);
+        assert_eq!(1, wheel.take_due().len());
+        wheel.advance(123_456 + 100_000);
+        assert_eq!(2, wheel.take_due().len());
+        assert_eq!(0, wheel.len);
+
+        // Entries that are already due when added become due right away.
+        wheel.insert(entry(5).0);
+        assert_eq!(1, wheel.take_due().len());
+    }
+
+    /// Tests that cancelled entries are dropped when their slot expires.
+    #[test]
+    fn test_wheel_cancel() {
+        let mut wheel = Wheel::new();
+        let (first, handle) = entry(10);
+        wheel.insert(first);
+        wheel.insert(entry(10_000).0);
+        handle.cancel();
+        wheel.advance(100);
+        assert_eq!(0, wheel.take_due().len());
+        assert_eq!(1, wheel.len);
+        assert_eq!(vec![(10_000, 10_000)], drain(&mut wheel));
+    }
+
+    /// Tests that cancelled entries are dropped from the wheel once enough entries of the
+    /// shard have been cancelled.
+    #[test]
+    fn test_timer_compact() {
+        // The threads of this timer are never started so nothing is sent.
+        let timer = Timer::new(Duration::from_millis(1), 1);
+        let delay = Duration::from_secs(60);
+        let handles: Vec<ScheduleHandle> = (0..COMPACT_THRESHOLD + 1)
+            .map(|_| timer.insert(target(), delay, None))
+            .collect();
+        let kept = timer.insert(target(), delay, None);
+        for handle in handles.iter() {
+            handle.cancel();
+            handle.cancel();
+        }
+        assert_eq!(COMPACT_THRESHOLD + 2, timer.len());
+        assert_eq!(
+            COMPACT_THRESHOLD + 1,
+            timer.shards[0].cancellations.load(Ordering::Relaxed)
+        );
+
+        timer.insert(target(), delay, None);
+        assert_eq!(2, timer.len());
+        assert!(!kept.is_cancelled());
+    }
+
+    /// Tests that messages are sent on time when the timer has several shards and a coarser
+    /// tick.
+    #[test]
+    fn test_timer_shards() {
+        init_test_log();
+
+        let config = ActorSystemConfig::default()
+            .thread_pool_size(2)
+            .timer_shards(4)
+            .timer_tick(Duration::from_millis(5));
+        let system = ActorSystem::create(config);
+        let aid = system.spawn().with((), simple_handler).unwrap();
+        await_received(&aid, 1, 1000).unwrap();
+
+        let start = Instant::now();
+        let handles: Vec<ScheduleHandle> = (0..8)
+            .map(|_| aid.send_new_after(11, Duration::from_millis(20)).unwrap())
+            .collect();
+        handles[0].cancel();
+        await_received(&aid, 8, 1000).unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(20));
+        sleep(10);
+        assert_eq!(8, aid.received().unwrap());
+        assert_eq!(0, system.data.timer.len());
+
+        system.trigger_and_await_shutdown(None);
+    }
+
+    /// Tests that actors awaiting a sleep are woken when it elapses without holding up the
+    /// other actors on the same thread.
+    #[test]
+    fn test_sleep() {
+        init_test_log();
+
+        let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(1));
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let spawn = |name: &'static str| {
+            let events = events.clone();
+            system
+                .spawn()
+                .with((), move |_: (), context: Context, message: Message| {
+                    let events = events.clone();
+                    async move {
+                        if let Some(millis) = message.content_as::<u64>() {
+                            let start = Instant::now();
+                            context.sleep(Duration::from_millis(*millis)).await;
+                            assert!(start.elapsed() >= Duration::from_millis(*millis));
+                            events.lock().unwrap().push(name);
+                        }
+                        Ok(Status::done(()))
+                    }
+                })
+                .unwrap()
+        };
+        let slow = spawn("slow");
+        let fast = spawn("fast");
+        await_received(&slow, 1, 1000).unwrap();
+        await_received(&fast, 1, 1000).unwrap();
+
+        slow.send_new(50_u64).unwrap();
+        fast.send_new(0_u64).unwrap();
+        await_received(&slow, 2, 1000).unwrap();
+        await_received(&fast, 2, 1000).unwrap();
+        assert_eq!(vec!["fast", "slow"], *events.lock().unwrap());
+
+        // Sleeps work outside of actors as well and are cancelled when dropped early.
+        futures::executor::block_on(system.sleep(Duration::from_millis(10)));
+        let sleep = system.sleep(Duration::from_secs(60));
+        let handle = sleep.handle.clone();
+        drop(sleep);
+        assert!(handle.is_cancelled());
+
+        system.trigger_and_await_shutdown(None);
+    }
+
+    /// Tests that a timeout completes with the output of the inner future when it's in time
+    /// and with an error otherwise.
+    #[test]
+    fn test_timeout() {
+        init_test_log();
+
+        let system = ActorSystem::create(ActorSystemConfig::default().thread_pool_size(2));
+        let aid = system
+            .spawn()
+            .with((), |_: (), context: Context, message: Message| async move {
+                if let Some(millis) = message.content_as::<u64>() {
+                    let inner = async {
+                        context.sleep(Duration::from_millis(*millis)).await;
+                        *millis
+                    };
+                    let result = context.timeout(Duration::from_millis(30), inner).await;
+                    if *millis < 30 {
+                        assert_eq!(Ok(*millis), result);
+                    } else {
+                        assert_eq!(Err(TimeoutError), result);
+                    }
+                }
+                Ok(Status::done(()))
+            })
+            .unwrap();
+        await_received(&aid, 1, 1000).unwrap();
+
+        aid.send_new(5_u64).unwrap();
+        aid.send_new(1000_u64).unwrap();
+        await_received(&aid, 3, 1000).unwrap();
+        assert!(system.is_actor_alive(&aid));
+
+        system.trigger_and_await_shutdown(None);
+    }
+}